@@ -0,0 +1,50 @@
+
+use test::Bencher;
+
+// Weighted samplers are stored as one entry per child, not one entry per unit of weight.  The
+// `large_weights` benchmarks would exhaust memory if the weights were expanded into a pool.
+
+fn bench_next(b: &mut Bencher, expr: &str) {
+    let search_path = Default::default();
+    let model = rvs::parse(&search_path, &format!("a = {};", expr)).unwrap();
+    let variable = model.get_variable_by_name("a").unwrap();
+
+    b.iter(|| {
+        let mut variable = variable.borrow_mut();
+        for _ in 0..1024 {
+            variable.next();
+        }
+    });
+}
+
+fn bench_parse(b: &mut Bencher, expr: &str) {
+    let search_path = Default::default();
+    let source = format!("a = {};", expr);
+
+    b.iter(|| rvs::parse(&search_path, &source).unwrap());
+}
+
+#[bench]
+fn with_replacement_large_weights_parse(b: &mut Bencher) {
+    bench_parse(b, "r{1_000_000_000: 0, 1: 1}");
+}
+
+#[bench]
+fn with_replacement_large_weights_next(b: &mut Bencher) {
+    bench_next(b, "r{1_000_000_000: 0, 1: 1}");
+}
+
+#[bench]
+fn without_replacement_large_weights_parse(b: &mut Bencher) {
+    bench_parse(b, "{1_000_000_000: 0, 1: 1}");
+}
+
+#[bench]
+fn without_replacement_large_weights_next(b: &mut Bencher) {
+    bench_next(b, "{1_000_000_000: 0, 1: 1}");
+}
+
+#[bench]
+fn without_replacement_many_children_next(b: &mut Bencher) {
+    bench_next(b, "{Expand(Sequence(4096))}");
+}
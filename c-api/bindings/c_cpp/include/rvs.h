@@ -14,10 +14,13 @@ extern "C" {
 typedef struct rvs_error rvs_error;
 typedef struct rvs_context rvs_context;
 typedef struct rvs_model rvs_model;
+typedef uint32_t (*rvs_fn)(const uint32_t* args, uint32_t nargs, void* userdata);
 
 rvs_context* rvs_context_new(const char* search_path, uint32_t seed, rvs_error* error);
 void rvs_context_free(rvs_context* context);
 void rvs_parse(rvs_context* context, const char* s, rvs_error* error);
+void rvs_set_arg(rvs_context* context, const char* key, const char* value);
+void rvs_register_fn(rvs_context* context, const char* name, uint32_t arity, rvs_fn function, void* userdata);
 
 rvs_model* rvs_model_new();
 rvs_model* rvs_transform(rvs_context* context, rvs_model* model, rvs_error* error);
@@ -26,7 +29,20 @@ void rvs_model_free(rvs_model* model);
 uint32_t rvs_get(rvs_model* model, const char* id);
 uint32_t rvs_next(rvs_model* model, uint32_t handle);
 uint32_t rvs_prev(rvs_model* model, uint32_t handle);
+uint32_t rvs_prev_n(rvs_model* model, uint32_t handle, uint32_t n);
+void rvs_set_history_depth(rvs_model* model, uint32_t handle, uint32_t depth);
+bool rvs_set_input(rvs_model* model, uint32_t handle, uint32_t value);
+bool rvs_release(rvs_model* model, uint32_t handle, uint32_t value);
+uint32_t rvs_region_len(rvs_model* model, uint32_t handle);
 bool rvs_done(rvs_model* model, uint32_t handle);
+bool rvs_injected(rvs_model* model, uint32_t handle);
+bool rvs_exhausted(rvs_model* model, uint32_t handle);
+int32_t rvs_next_i32(rvs_model* model, uint32_t handle);
+int32_t rvs_prev_i32(rvs_model* model, uint32_t handle);
+int64_t rvs_next_i64(rvs_model* model, uint32_t handle);
+int64_t rvs_prev_i64(rvs_model* model, uint32_t handle);
+uint32_t rvs_width(rvs_model* model, uint32_t handle);
+bool rvs_overflowed(rvs_model* model, uint32_t handle);
 
 rvs_error* rvs_error_new();
 void rvs_error_free(rvs_error* error);
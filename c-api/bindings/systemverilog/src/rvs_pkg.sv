@@ -11,6 +11,7 @@ package rvs_pkg;
     import "DPI-C" function rvs_context rvs_context_new(string search_path, uint32_t seed, rvs_error error);
     import "DPI-C" function void rvs_context_free(rvs_context ctxt);
     import "DPI-C" function void rvs_parse(rvs_context ctxt, string s, rvs_error error);
+    import "DPI-C" function void rvs_set_arg(rvs_context ctxt, string key, string value);
 
     import "DPI-C" function rvs_model rvs_model_new();
     import "DPI-C" function void rvs_transform(rvs_context ctxt, rvs_model model, rvs_error error);
@@ -19,7 +20,20 @@ package rvs_pkg;
     import "DPI-C" function rvs_handle rvs_get(rvs_model model, string name);
     import "DPI-C" function rvs_result rvs_next(rvs_model model, rvs_handle handle);
     import "DPI-C" function rvs_result rvs_prev(rvs_model model, rvs_handle handle);
+    import "DPI-C" function rvs_result rvs_prev_n(rvs_model model, rvs_handle handle, uint32_t n);
+    import "DPI-C" function void rvs_set_history_depth(rvs_model model, rvs_handle handle, uint32_t depth);
+    import "DPI-C" function bit rvs_set_input(rvs_model model, rvs_handle handle, uint32_t value);
+    import "DPI-C" function bit rvs_release(rvs_model model, rvs_handle handle, uint32_t value);
+    import "DPI-C" function uint32_t rvs_region_len(rvs_model model, rvs_handle handle);
     import "DPI-C" function bit rvs_done(rvs_model model, rvs_handle handle);
+    import "DPI-C" function bit rvs_injected(rvs_model model, rvs_handle handle);
+    import "DPI-C" function bit rvs_exhausted(rvs_model model, rvs_handle handle);
+    import "DPI-C" function int rvs_next_i32(rvs_model model, rvs_handle handle);
+    import "DPI-C" function int rvs_prev_i32(rvs_model model, rvs_handle handle);
+    import "DPI-C" function longint rvs_next_i64(rvs_model model, rvs_handle handle);
+    import "DPI-C" function longint rvs_prev_i64(rvs_model model, rvs_handle handle);
+    import "DPI-C" function uint32_t rvs_width(rvs_model model, rvs_handle handle);
+    import "DPI-C" function bit rvs_overflowed(rvs_model model, rvs_handle handle);
     import "DPI-C" function void rvs_write_definitions(rvs_model model, string name, rvs_error error);
 
     import "DPI-C" function rvs_error rvs_error_new();
@@ -97,6 +111,18 @@ package rvs_pkg;
             `rvs_handle_error
         endfunction
 
+        // Function: set_arg
+        //
+        // Sets the value returned by arg("<key>", <default>) lookups.  Must be
+        // called before transform.
+        static function void set_arg(string key, string value);
+            if (!ctxt) begin
+                $fatal(1, "set_arg called before initialize() or after transform()");
+            end
+
+            rvs_set_arg(ctxt, key, value);
+        endfunction
+
         static function void transform();
             rvs_transform(ctxt, model, error);
             `rvs_handle_error
@@ -197,10 +223,76 @@ package rvs_pkg;
             return T'(rvs_prev(model, handle));
         endfunction
 
+        // Function: prev_n
+        //
+        // Returns the nth most recent value.  prev_n(1) is the same as
+        // prev().  Returns 0 beyond the history depth.
+        function T prev_n(uint32_t n);
+            return T'(rvs_prev_n(model, handle, n));
+        endfunction
+
+        function void set_history_depth(uint32_t depth);
+            rvs_set_history_depth(model, handle, depth);
+        endfunction
+
+        // Function: set_input
+        //
+        // Sets the value of an input variable.  Returns 0 if the variable is
+        // not an input.
+        function bit set_input(T value);
+            return rvs_set_input(model, handle, uint32_t'(value));
+        endfunction
+
+        // Function: release
+        //
+        // Returns a value so that next() may return it again.  Returns 0 if
+        // the variable does not support release or the value is not in use.
+        function bit release(T value);
+            return rvs_release(model, handle, uint32_t'(value));
+        endfunction
+
+        // Function: region_len
+        //
+        // Returns the length of the region that starts at prev().  Returns 0
+        // if the variable does not return regions.
+        function uint32_t region_len();
+            return rvs_region_len(model, handle);
+        endfunction
+
         function bit done();
             return rvs_done(model, handle);
         endfunction
 
+        // Function: injected
+        //
+        // Returns 1 if the previous value was corrupted by Inject.
+        function bit injected();
+            return rvs_injected(model, handle);
+        endfunction
+
+        // Function: exhausted
+        //
+        // Returns 1 if the previous call to next found every value in use.
+        function bit exhausted();
+            return rvs_exhausted(model, handle);
+        endfunction
+
+        // Function: width
+        //
+        // Returns the declared width of the variable in bits.  Variables
+        // without a width annotation are 32 bits wide.
+        function uint32_t width();
+            return rvs_width(model, handle);
+        endfunction
+
+        // Function: overflowed
+        //
+        // Returns 1 if the previous value did not fit in the declared width
+        // under the error overflow policy.  The value was truncated.
+        function bit overflowed();
+            return rvs_overflowed(model, handle);
+        endfunction
+
         function string get_name();
             return name;
         endfunction
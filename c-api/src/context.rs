@@ -8,6 +8,8 @@ pub struct Context {
     parser: rvs::Parser,
     seed: rvs::Seed,
     search_path: rvs::SearchPath,
+    functions: Vec<(String, rvs::Function)>,
+    args: Vec<(String, String)>,
 }
 
 impl Context {
@@ -16,6 +18,8 @@ impl Context {
             parser: rvs::Parser::new(&search_path),
             seed,
             search_path,
+            functions: Vec::new(),
+            args: Vec::new(),
         }
     }
 
@@ -23,8 +27,25 @@ impl Context {
         self.parser.parse(s)
     }
 
+    /// Registers a host function with every model on `transform`
+    pub fn register_fn(&mut self, name: &str, arity: usize, callback: rvs::Callback) {
+        self.functions.push((name.into(), rvs::Function::new(arity, callback)));
+    }
+
+    /// Sets the value of `arg("<key>", <default>)` lookups on `transform`
+    pub fn set_arg(&mut self, key: &str, value: &str) {
+        self.args.push((key.into(), value.into()));
+    }
+
     pub fn transform(&self, model: &mut rvs::Model) -> rvs::Result<()> {
+        for (name, function) in &self.functions {
+            model.add_function(name, function.clone());
+        }
+
         let mut transform = rvs::Transform::new(self.seed.clone());
+        for (key, value) in &self.args {
+            transform.set_arg(key, value);
+        }
 
         transform.transform(model, self.parser.ast())?;
 
@@ -4,7 +4,7 @@
 
 #![cfg_attr(feature = "cargo-clippy", allow(not_unsafe_ptr_arg_deref))]
 
-use libc::c_char;
+use libc::{c_char, c_void};
 use std::ffi::CStr;
 use std::path::Path;
 use std::fs::File;
@@ -17,6 +17,10 @@ use crate::error::Error;
 use crate::error::ErrorKind;
 
 type SequenceHandleRaw = u32;
+
+/// A host function called with its argument values, the number of arguments, and the userdata
+/// pointer passed to `rvs_register_fn`
+pub type FunctionRaw = extern "C" fn(args: *const u32, nargs: u32, userdata: *mut c_void) -> u32;
 struct SequenceHandle(SequenceHandleRaw);
 
 impl SequenceHandle {
@@ -176,6 +180,67 @@ pub extern "C" fn rvs_parse(context: *mut Context, s: *const c_char, error: *mut
     }
 }
 
+/// Sets the value returned by `arg("<key>", <default>)` lookups
+///
+/// Must be called before `rvs_transform`.  The value must be a number literal.  E.g. `64`,
+/// `0x40`, or `4K`.  Otherwise, `rvs_transform` reports an error for lookups of `key`.
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_set_arg(context: *mut Context, key: *const c_char, value: *const c_char) {
+    assert!(!context.is_null());
+    assert!(!key.is_null());
+    assert!(!value.is_null());
+
+    let key_cstr = unsafe { CStr::from_ptr(key) };
+    let key_rstr = key_cstr.to_str().unwrap();
+    let value_cstr = unsafe { CStr::from_ptr(value) };
+    let value_rstr = value_cstr.to_str().unwrap();
+
+    let context = unsafe { &mut *context };
+    context.set_arg(key_rstr, value_rstr);
+}
+
+/// Registers a host function that expressions call as `<name>(<args>)`
+///
+/// Must be called before `rvs_transform`.  The function is called on every draw of an expression
+/// that calls it.  The transform reports an error if an expression calls an unregistered function
+/// or passes a number of arguments other than `arity`.
+///
+/// # Arguments
+///
+/// * `name` - The name of the function.  Must start with a lowercase letter or underscore.
+/// * `arity` - The number of arguments of the function.
+/// * `function` - The host function.
+/// * `userdata` - An opaque pointer passed to every call of `function`.
+///
+/// # Panics
+///
+/// * If `context` or `name` are null
+#[no_mangle]
+pub extern "C" fn rvs_register_fn(
+    context: *mut Context,
+    name: *const c_char,
+    arity: u32,
+    function: FunctionRaw,
+    userdata: *mut c_void,
+) {
+    assert!(!context.is_null());
+    assert!(!name.is_null());
+
+    let name_cstr = unsafe { CStr::from_ptr(name) };
+    let name_rstr = name_cstr.to_str().unwrap();
+
+    let context = unsafe { &mut *context };
+    context.register_fn(
+        name_rstr,
+        arity as usize,
+        Box::new(move |args: &[u32]| function(args.as_ptr(), args.len() as u32, userdata)),
+    );
+}
+
 /// Creates a new Model
 ///
 /// The pointer returned is owned by the caller and is freed by a call to `rvs_model_free`.
@@ -309,6 +374,133 @@ pub extern "C" fn rvs_prev(model: *mut rvs::Model, handle: SequenceHandleRaw) ->
     }
 }
 
+/// Returns the `n`th most recent value of a variable
+///
+/// `rvs_prev_n(model, handle, 1)` is the same as `rvs_prev(model, handle)`.  Values older than
+/// the history depth of the variable are not kept.  See `rvs_set_history_depth`.
+///
+/// # Errors
+///
+/// * Returns 0 if handle is invalid
+/// * Returns 0 if `n` is 0 or greater than the history depth
+/// * Returns 0 if `rvs_next` has not been called at least `n` times
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_prev_n(model: *mut rvs::Model, handle: SequenceHandleRaw, n: u32) -> u32 {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    match model.get_variable_by_index(handle.into()) {
+        Some(variable) => variable.borrow().history(n as usize),
+        None => 0,
+    }
+}
+
+/// Sets the number of past values kept by a variable
+///
+/// The depth is at least 1.  References such as `a.prev(3)` increase the depth automatically.
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_set_history_depth(
+    model: *mut rvs::Model,
+    handle: SequenceHandleRaw,
+    depth: u32,
+) {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    if let Some(variable) = model.get_variable_by_index(handle.into()) {
+        variable.borrow_mut().set_history_depth(depth as usize);
+    }
+}
+
+/// Sets the value of an input variable
+///
+/// E.g. `input depth = 0;`.  Dependent expressions read the value on their next draw.
+///
+/// # Errors
+///
+/// * Returns false if handle is invalid
+/// * Returns false if the variable is not an input
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_set_input(model: *mut rvs::Model, handle: SequenceHandleRaw, value: u32) -> bool {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    match model.get_variable_by_index(handle.into()) {
+        Some(variable) => variable.borrow_mut().set(value),
+        None => false,
+    }
+}
+
+/// Returns a value to a variable so that it may be returned by `rvs_next` again
+///
+/// E.g. frees an ID returned by `Ids(lo, hi)` or a region returned by `Alloc(base, size, len,
+/// align)`.
+///
+/// # Errors
+///
+/// * Returns false if handle is invalid
+/// * Returns false if the variable does not support release or the value is not in use
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_release(model: *mut rvs::Model, handle: SequenceHandleRaw, value: u32) -> bool {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    match model.get_variable_by_index(handle.into()) {
+        Some(variable) => variable.borrow_mut().release(value),
+        None => false,
+    }
+}
+
+/// Returns the length of the region returned by the most recent call to `rvs_next`
+///
+/// The region starts at the value returned by `rvs_next`.  Pass the start to `rvs_release` to
+/// free the region.
+///
+/// # Errors
+///
+/// * Returns 0 if handle is invalid
+/// * Returns 0 if the variable does not return regions.  E.g. `Alloc(base, size, len, align)`.
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_region_len(model: *mut rvs::Model, handle: SequenceHandleRaw) -> u32 {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    match model.get_variable_by_index(handle.into()) {
+        Some(variable) => variable.borrow().region_len(),
+        None => 0,
+    }
+}
+
 /// Returns the done value of a variable via the result pointer
 ///
 /// # Errors
@@ -333,6 +525,196 @@ pub extern "C" fn rvs_done(model: *mut rvs::Model, handle: SequenceHandleRaw) ->
     }
 }
 
+/// Returns whether the previous value of a variable was corrupted
+///
+/// Only values corrupted by an `Inject(expr, percent, corruption)` at the top of the variable
+/// expression are reported.
+///
+/// # Errors
+///
+/// * Returns false if handle is invalid
+/// * Returns false if `rvs_next` has not been called
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_injected(model: *mut rvs::Model, handle: SequenceHandleRaw) -> bool {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    match model.get_variable_by_index(handle.into()) {
+        Some(variable) => variable.borrow().injected(),
+        None => false,
+    }
+}
+
+/// Returns whether the previous call to `rvs_next` found every value in use
+///
+/// The value returned by that call is not a new value.  E.g. `Ids(lo, hi)`.
+///
+/// # Errors
+///
+/// * Returns false if handle is invalid
+/// * Returns false if `rvs_next` has not been called
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_exhausted(model: *mut rvs::Model, handle: SequenceHandleRaw) -> bool {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    match model.get_variable_by_index(handle.into()) {
+        Some(variable) => variable.borrow().exhausted(),
+        None => false,
+    }
+}
+
+/// Returns the next value of a variable as a two's complement signed value
+///
+/// # Errors
+///
+/// Returns 0 if handle is invalid.
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_next_i32(model: *mut rvs::Model, handle: SequenceHandleRaw) -> i32 {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+    match model.get_variable_by_index(handle.into()) {
+        Some(variable) => variable.borrow_mut().next_i32(),
+        None => 0,
+    }
+}
+
+/// Returns the previous value of a variable as a two's complement signed value
+///
+/// # Errors
+///
+/// * Returns 0 if handle is invalid
+/// * Returns 0 if `rvs_next` has not been called
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_prev_i32(model: *mut rvs::Model, handle: SequenceHandleRaw) -> i32 {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+    match model.get_variable_by_index(handle.into()) {
+        Some(variable) => variable.borrow().prev_i32(),
+        None => 0,
+    }
+}
+
+/// Returns the next value of a variable as a 64-bit value
+///
+/// The value is sign extended if the variable is signed and zero extended otherwise.
+///
+/// # Errors
+///
+/// Returns 0 if handle is invalid.
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_next_i64(model: *mut rvs::Model, handle: SequenceHandleRaw) -> i64 {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+    match model.get_variable_by_index(handle.into()) {
+        Some(variable) => variable.borrow_mut().next_i64(),
+        None => 0,
+    }
+}
+
+/// Returns the previous value of a variable as a 64-bit value
+///
+/// The value is sign extended if the variable is signed and zero extended otherwise.
+///
+/// # Errors
+///
+/// * Returns 0 if handle is invalid
+/// * Returns 0 if `rvs_next` has not been called
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_prev_i64(model: *mut rvs::Model, handle: SequenceHandleRaw) -> i64 {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+    match model.get_variable_by_index(handle.into()) {
+        Some(variable) => variable.borrow().prev_i64(),
+        None => 0,
+    }
+}
+
+/// Returns the declared width of a variable in bits
+///
+/// Variables without a width annotation are 32 bits wide.
+///
+/// # Errors
+///
+/// * Returns 0 if handle is invalid
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_width(model: *mut rvs::Model, handle: SequenceHandleRaw) -> u32 {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    match model.get_variable_by_index(handle.into()) {
+        Some(variable) => variable.borrow().width(),
+        None => 0,
+    }
+}
+
+/// Returns whether the previous value of a variable did not fit in its declared width
+///
+/// Only set under the `error` overflow policy.  The value returned by `rvs_next` was truncated.
+///
+/// # Errors
+///
+/// * Returns false if handle is invalid
+/// * Returns false if `rvs_next` has not been called
+///
+/// # Panics
+///
+/// * If any pointer arguments are null
+#[no_mangle]
+pub extern "C" fn rvs_overflowed(model: *mut rvs::Model, handle: SequenceHandleRaw) -> bool {
+    assert!(!model.is_null());
+
+    let model = unsafe { &mut *model };
+    let handle = SequenceHandle(handle);
+
+    match model.get_variable_by_index(handle.into()) {
+        Some(variable) => variable.borrow().overflowed(),
+        None => false,
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn rvs_write_definitions(
     model: *const rvs::Model,
@@ -34,7 +34,18 @@ fn next_by_name(model: *mut rvs::Model, name: &str) -> u32 {
 
 mod rvs_context_new;
 mod rvs_seed;
+mod rvs_overflowed;
 mod rvs_parse;
+mod rvs_exhausted;
 mod rvs_get;
+mod rvs_injected;
 mod rvs_next;
+mod rvs_next_signed;
+mod rvs_prev_n;
+mod rvs_register_fn;
+mod rvs_region_len;
+mod rvs_release;
+mod rvs_set_arg;
+mod rvs_set_input;
+mod rvs_width;
 mod rvs_write_definitions;
@@ -0,0 +1,34 @@
+use super::*;
+
+#[test]
+fn basic() {
+    let error = rvs_error_new();
+    let context = rvs_context_new(CString::new("").unwrap().as_ptr(), 0, error);
+    assert!(!rvs_error_test(error));
+
+    rvs_parse(context, CString::new("a = Ids(3, 3);").unwrap().as_ptr(), error);
+    assert!(!rvs_error_test(error));
+
+    let model = rvs_model_new();
+    rvs_transform(context, model, error);
+    assert!(!rvs_error_test(error));
+
+    let a = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    assert!(!rvs_exhausted(model, a));
+    assert_eq!(rvs_next(model, a), 3);
+    assert!(!rvs_exhausted(model, a));
+
+    assert_eq!(rvs_next(model, a), 3);
+    assert!(rvs_exhausted(model, a));
+    assert!(rvs_done(model, a));
+
+    assert!(rvs_release(model, a, 3));
+    assert_eq!(rvs_next(model, a), 3);
+    assert!(!rvs_exhausted(model, a));
+
+    assert!(!rvs_exhausted(model, 3));
+
+    rvs_error_free(error);
+    rvs_model_free(model);
+}
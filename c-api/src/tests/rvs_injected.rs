@@ -0,0 +1,36 @@
+use super::*;
+
+#[test]
+fn basic() {
+    let error = rvs_error_new();
+    let context = rvs_context_new(CString::new("").unwrap().as_ptr(), 0, error);
+    assert!(!rvs_error_test(error));
+
+    rvs_parse(
+        context,
+        CString::new("a = Inject(5, 100, replace(7)); b = Inject(5, 0, replace(7));")
+            .unwrap()
+            .as_ptr(),
+        error,
+    );
+    assert!(!rvs_error_test(error));
+
+    let model = rvs_model_new();
+    rvs_transform(context, model, error);
+    assert!(!rvs_error_test(error));
+
+    let a = rvs_get(model, CString::new("a").unwrap().as_ptr());
+    let b = rvs_get(model, CString::new("b").unwrap().as_ptr());
+
+    assert!(!rvs_injected(model, a));
+    assert_eq!(rvs_next(model, a), 7);
+    assert!(rvs_injected(model, a));
+
+    assert_eq!(rvs_next(model, b), 5);
+    assert!(!rvs_injected(model, b));
+
+    assert!(!rvs_injected(model, 3));
+
+    rvs_error_free(error);
+    rvs_model_free(model);
+}
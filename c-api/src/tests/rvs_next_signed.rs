@@ -0,0 +1,32 @@
+use super::*;
+
+#[test]
+fn basic() {
+    let error = rvs_error_new();
+    let context = rvs_context_new(CString::new("").unwrap().as_ptr(), 0, error);
+    assert!(!rvs_error_test(error));
+
+    rvs_parse(context, CString::new("a: i8 = -5; b = -5;").unwrap().as_ptr(), error);
+    assert!(!rvs_error_test(error));
+
+    let model = rvs_model_new();
+    rvs_transform(context, model, error);
+    assert!(!rvs_error_test(error));
+
+    let a = rvs_get(model, CString::new("a").unwrap().as_ptr());
+    let b = rvs_get(model, CString::new("b").unwrap().as_ptr());
+
+    assert_eq!(rvs_next_i32(model, a), -5);
+    assert_eq!(rvs_prev_i32(model, a), -5);
+    assert_eq!(rvs_next_i64(model, a), -5);
+    assert_eq!(rvs_prev_i64(model, a), -5);
+
+    assert_eq!(rvs_next_i32(model, b), -5);
+    assert_eq!(rvs_next_i64(model, b), 0xffff_fffb);
+    assert_eq!(rvs_prev_i64(model, b), 0xffff_fffb);
+
+    assert_eq!(rvs_next_i64(model, 3), 0);
+
+    rvs_error_free(error);
+    rvs_model_free(model);
+}
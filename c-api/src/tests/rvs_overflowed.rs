@@ -0,0 +1,35 @@
+use super::*;
+
+#[test]
+fn basic() {
+    let error = rvs_error_new();
+    let context = rvs_context_new(CString::new("").unwrap().as_ptr(), 0, error);
+    assert!(!rvs_error_test(error));
+
+    rvs_parse(
+        context,
+        CString::new("a: u5 error = Pattern(0x1f, 0x25); b: u5 = 0x25;").unwrap().as_ptr(),
+        error,
+    );
+    assert!(!rvs_error_test(error));
+
+    let model = rvs_model_new();
+    rvs_transform(context, model, error);
+    assert!(!rvs_error_test(error));
+
+    let a = rvs_get(model, CString::new("a").unwrap().as_ptr());
+    let b = rvs_get(model, CString::new("b").unwrap().as_ptr());
+
+    assert_eq!(rvs_next(model, a), 0x1f);
+    assert!(!rvs_overflowed(model, a));
+    assert_eq!(rvs_next(model, a), 0x5);
+    assert!(rvs_overflowed(model, a));
+
+    assert_eq!(rvs_next(model, b), 0x5);
+    assert!(!rvs_overflowed(model, b));
+
+    assert!(!rvs_overflowed(model, 3));
+
+    rvs_error_free(error);
+    rvs_model_free(model);
+}
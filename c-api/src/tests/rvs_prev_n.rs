@@ -0,0 +1,32 @@
+use super::*;
+
+#[test]
+fn basic() {
+    let error = rvs_error_new();
+    let context = rvs_context_new(CString::new("").unwrap().as_ptr(), 0, error);
+    assert!(!rvs_error_test(error));
+
+    rvs_parse(context, CString::new("a = Sequence(10);").unwrap().as_ptr(), error);
+    assert!(!rvs_error_test(error));
+
+    let model = rvs_model_new();
+    rvs_transform(context, model, error);
+    assert!(!rvs_error_test(error));
+
+    let handle = rvs_get(model, CString::new("a").unwrap().as_ptr());
+    rvs_set_history_depth(model, handle, 3);
+
+    for _ in 0..4 {
+        rvs_next(model, handle);
+    }
+
+    assert_eq!(rvs_prev_n(model, handle, 1), 3);
+    assert_eq!(rvs_prev_n(model, handle, 2), 2);
+    assert_eq!(rvs_prev_n(model, handle, 3), 1);
+    assert_eq!(rvs_prev_n(model, handle, 4), 0);
+    assert_eq!(rvs_prev_n(model, handle, 0), 0);
+    assert_eq!(rvs_prev_n(model, 2, 1), 0);
+
+    rvs_error_free(error);
+    rvs_model_free(model);
+}
@@ -0,0 +1,40 @@
+use super::*;
+
+#[test]
+fn basic() {
+    let error = rvs_error_new();
+    let context = rvs_context_new(CString::new("").unwrap().as_ptr(), 0, error);
+    assert!(!rvs_error_test(error));
+
+    rvs_parse(
+        context,
+        CString::new("a = Alloc(0x100, 0x40, 0x20, 0x20); b = 5;").unwrap().as_ptr(),
+        error,
+    );
+    assert!(!rvs_error_test(error));
+
+    let model = rvs_model_new();
+    rvs_transform(context, model, error);
+    assert!(!rvs_error_test(error));
+
+    let a = rvs_get(model, CString::new("a").unwrap().as_ptr());
+    let b = rvs_get(model, CString::new("b").unwrap().as_ptr());
+
+    assert_eq!(rvs_region_len(model, a), 0);
+
+    let first = rvs_next(model, a);
+    let second = rvs_next(model, a);
+    assert_eq!(first + second, 0x100 + 0x120);
+    assert_eq!(rvs_region_len(model, a), 0x20);
+
+    assert!(rvs_release(model, a, second));
+    assert!(!rvs_release(model, a, second));
+    assert_eq!(rvs_next(model, a), second);
+
+    rvs_next(model, b);
+    assert_eq!(rvs_region_len(model, b), 0);
+    assert_eq!(rvs_region_len(model, 3), 0);
+
+    rvs_error_free(error);
+    rvs_model_free(model);
+}
@@ -0,0 +1,106 @@
+use super::*;
+
+use libc::c_void;
+
+use std::cell::Cell;
+
+extern "C" fn mem_free(_args: *const u32, nargs: u32, userdata: *mut c_void) -> u32 {
+    assert_eq!(nargs, 0);
+
+    unsafe { (*(userdata as *const Cell<u32>)).get() }
+}
+
+extern "C" fn sum(args: *const u32, nargs: u32, _userdata: *mut c_void) -> u32 {
+    let args = unsafe { ::std::slice::from_raw_parts(args, nargs as usize) };
+
+    args.iter().sum()
+}
+
+#[test]
+fn basic() {
+    let free = Cell::new(4u32);
+
+    let error = rvs_error_new();
+    let context = rvs_context_new(CString::new("").unwrap().as_ptr(), 0, error);
+    assert!(!rvs_error_test(error));
+
+    rvs_register_fn(
+        context,
+        CString::new("mem_free").unwrap().as_ptr(),
+        0,
+        mem_free,
+        &free as *const Cell<u32> as *mut c_void,
+    );
+    rvs_register_fn(context, CString::new("sum").unwrap().as_ptr(), 2, sum, ::std::ptr::null_mut());
+
+    rvs_parse(
+        context,
+        CString::new("a = mem_free(); b = sum(1, 2);").unwrap().as_ptr(),
+        error,
+    );
+    assert!(!rvs_error_test(error));
+
+    let model = rvs_model_new();
+    rvs_transform(context, model, error);
+    assert!(!rvs_error_test(error));
+
+    assert_eq!(next_by_name(model, "a"), 4);
+    free.set(9);
+    assert_eq!(next_by_name(model, "a"), 9);
+    assert_eq!(next_by_name(model, "b"), 3);
+
+    rvs_error_free(error);
+    rvs_model_free(model);
+}
+
+#[test]
+fn arity() {
+    let error = rvs_error_new();
+    let context = rvs_context_new(CString::new("").unwrap().as_ptr(), 0, error);
+
+    rvs_register_fn(context, CString::new("sum").unwrap().as_ptr(), 2, sum, ::std::ptr::null_mut());
+    rvs_parse(context, CString::new("a = sum(1);").unwrap().as_ptr(), error);
+    assert!(!rvs_error_test(error));
+
+    let model = rvs_model_new();
+    rvs_transform(context, model, error);
+    assert!(rvs_error_test(error));
+    assert_starts_with(get_error_message(error), "Expected 2 arguments for function 'sum'");
+
+    rvs_error_free(error);
+    rvs_model_free(model);
+}
+
+#[test]
+fn shared_between_models() {
+    let free = Cell::new(4u32);
+
+    let error = rvs_error_new();
+    let context = rvs_context_new(CString::new("").unwrap().as_ptr(), 0, error);
+
+    rvs_register_fn(
+        context,
+        CString::new("mem_free").unwrap().as_ptr(),
+        0,
+        mem_free,
+        &free as *const Cell<u32> as *mut c_void,
+    );
+    rvs_parse(context, CString::new("a = mem_free();").unwrap().as_ptr(), error);
+    assert!(!rvs_error_test(error));
+
+    let first = rvs_model_new();
+    let second = rvs_model_new();
+    unsafe {
+        (*context).transform(&mut *first).unwrap();
+        (*context).transform(&mut *second).unwrap();
+    }
+    rvs_context_free(context);
+
+    assert_eq!(next_by_name(first, "a"), 4);
+    free.set(9);
+    assert_eq!(next_by_name(second, "a"), 9);
+
+    rvs_error_free(error);
+    rvs_model_free(first);
+    rvs_model_free(second);
+}
@@ -0,0 +1,34 @@
+use super::*;
+
+#[test]
+fn basic() {
+    let error = rvs_error_new();
+    let context = rvs_context_new(CString::new("").unwrap().as_ptr(), 0, error);
+    assert!(!rvs_error_test(error));
+
+    rvs_parse(context, CString::new("a = Ids(0, 1); b = 5;").unwrap().as_ptr(), error);
+    assert!(!rvs_error_test(error));
+
+    let model = rvs_model_new();
+    rvs_transform(context, model, error);
+    assert!(!rvs_error_test(error));
+
+    let a = rvs_get(model, CString::new("a").unwrap().as_ptr());
+    let b = rvs_get(model, CString::new("b").unwrap().as_ptr());
+
+    let first = rvs_next(model, a);
+    let second = rvs_next(model, a);
+    assert_eq!(first + second, 1);
+    assert!(rvs_done(model, a));
+
+    assert!(rvs_release(model, a, first));
+    assert!(!rvs_release(model, a, first));
+    assert!(!rvs_release(model, a, 2));
+    assert_eq!(rvs_next(model, a), first);
+
+    assert!(!rvs_release(model, b, 5));
+    assert!(!rvs_release(model, 3, 0));
+
+    rvs_error_free(error);
+    rvs_model_free(model);
+}
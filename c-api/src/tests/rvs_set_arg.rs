@@ -0,0 +1,52 @@
+use super::*;
+
+#[test]
+fn basic() {
+    let error = rvs_error_new();
+    let context = rvs_context_new(CString::new("").unwrap().as_ptr(), 0, error);
+    assert!(!rvs_error_test(error));
+
+    rvs_set_arg(
+        context,
+        CString::new("max_len").unwrap().as_ptr(),
+        CString::new("0x80").unwrap().as_ptr(),
+    );
+    rvs_parse(
+        context,
+        CString::new("a = arg(\"max_len\", 64); b = arg(\"min_len\", 1);").unwrap().as_ptr(),
+        error,
+    );
+    assert!(!rvs_error_test(error));
+
+    let model = rvs_model_new();
+    rvs_transform(context, model, error);
+    assert!(!rvs_error_test(error));
+
+    assert_eq!(next_by_name(model, "a"), 0x80);
+    assert_eq!(next_by_name(model, "b"), 1);
+
+    rvs_error_free(error);
+    rvs_model_free(model);
+}
+
+#[test]
+fn not_a_number() {
+    let error = rvs_error_new();
+    let context = rvs_context_new(CString::new("").unwrap().as_ptr(), 0, error);
+
+    rvs_set_arg(
+        context,
+        CString::new("max_len").unwrap().as_ptr(),
+        CString::new("long").unwrap().as_ptr(),
+    );
+    rvs_parse(context, CString::new("a = arg(\"max_len\", 64);").unwrap().as_ptr(), error);
+    assert!(!rvs_error_test(error));
+
+    let model = rvs_model_new();
+    rvs_transform(context, model, error);
+    assert!(rvs_error_test(error));
+    assert_starts_with(get_error_message(error), "Expected a number for arg(\"max_len\")");
+
+    rvs_error_free(error);
+    rvs_model_free(model);
+}
@@ -0,0 +1,32 @@
+use super::*;
+
+#[test]
+fn basic() {
+    let error = rvs_error_new();
+    let context = rvs_context_new(CString::new("").unwrap().as_ptr(), 0, error);
+    assert!(!rvs_error_test(error));
+
+    rvs_parse(
+        context,
+        CString::new("input depth = 2; a = depth * 2;").unwrap().as_ptr(),
+        error,
+    );
+    assert!(!rvs_error_test(error));
+
+    let model = rvs_model_new();
+    rvs_transform(context, model, error);
+    assert!(!rvs_error_test(error));
+
+    let depth = rvs_get(model, CString::new("depth").unwrap().as_ptr());
+    let a = rvs_get(model, CString::new("a").unwrap().as_ptr());
+
+    assert_eq!(rvs_next(model, a), 4);
+    assert!(rvs_set_input(model, depth, 5));
+    assert_eq!(rvs_next(model, a), 10);
+
+    assert!(!rvs_set_input(model, a, 1));
+    assert!(!rvs_set_input(model, 3, 1));
+
+    rvs_error_free(error);
+    rvs_model_free(model);
+}
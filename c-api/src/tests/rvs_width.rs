@@ -0,0 +1,26 @@
+use super::*;
+
+#[test]
+fn basic() {
+    let error = rvs_error_new();
+    let context = rvs_context_new(CString::new("").unwrap().as_ptr(), 0, error);
+    assert!(!rvs_error_test(error));
+
+    rvs_parse(context, CString::new("a: u5 = 0x3f; b = 1;").unwrap().as_ptr(), error);
+    assert!(!rvs_error_test(error));
+
+    let model = rvs_model_new();
+    rvs_transform(context, model, error);
+    assert!(!rvs_error_test(error));
+
+    let a = rvs_get(model, CString::new("a").unwrap().as_ptr());
+    let b = rvs_get(model, CString::new("b").unwrap().as_ptr());
+
+    assert_eq!(rvs_width(model, a), 5);
+    assert_eq!(rvs_next(model, a), 0x1f);
+    assert_eq!(rvs_width(model, b), 32);
+    assert_eq!(rvs_width(model, 3), 0);
+
+    rvs_error_free(error);
+    rvs_model_free(model);
+}
@@ -2,9 +2,17 @@ use std::fmt;
 
 #[derive(Debug, Clone)]
 pub enum BinaryOpcode {
+    LogicalOr,
+    LogicalAnd,
     Or,
     Xor,
     And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
     Shl,
     Shr,
     Add,
@@ -18,6 +26,7 @@ pub enum BinaryOpcode {
 pub enum UnaryOpcode {
     Inv,
     Neg,
+    Not,
 }
 
 #[derive(Debug)]
@@ -25,11 +34,69 @@ pub enum Type {
     Pattern,
     Sequence,
     Range,
+    Cyclic,
+    Burst,
+    Walk,
+    Near,
+    WalkingOnes,
+    WalkingZeros,
+    Gray,
+    Lfsr,
+    Checkerboard,
+    RandomBits,
+    Combo,
+    Repeat,
+    Take,
+    Skip,
+    Cycle,
+    NoRepeat,
+    Ids,
+    Alloc,
+    Inject(Corruption),
+    Where,
     Expand,
     Done,
     Once,
 }
 
+/// Corruption applied by `Inject`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Corruption {
+    /// Flip a number of random bits
+    Flip,
+    /// Replace with the value of an expression
+    Replace,
+    /// XOR with the value of an expression
+    Xor,
+}
+
+/// The source of the value of a `env()` or `arg()` lookup
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Lookup {
+    /// An environment variable
+    Env,
+    /// An argument supplied to the transform
+    Arg,
+}
+
+/// Operands that must be done for an operation to be done
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DonePolicy {
+    All,
+    Any,
+    Left,
+    Right,
+}
+
+/// Whether operand done flags reset when an operation is done
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DoneReset {
+    /// Operand done flags are never reset
+    Sticky,
+    /// Operand done flags are reset each time the operation is done
+    Periodic,
+}
+
 #[derive(Debug)]
 pub enum Replacement {
     With,
@@ -40,21 +107,55 @@ pub enum Replacement {
 pub enum VariableMethod {
     Next,
     Prev,
+    /// The nth most recent value E.g. `a.prev(2)`
+    PrevN(u32),
     Copy,
 }
 
+/// Handling of values that do not fit in the declared width of a variable
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Overflow {
+    /// Keep the least significant bits
+    Truncate,
+    /// Clamp to the largest value that fits
+    Saturate,
+    /// Panic
+    Error,
+}
+
+/// A width annotation on a variable declaration
+///
+/// E.g. `a: u5 saturate = x + y;` or `b: i8 = [-5, 5];`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Width {
+    pub bits: u32,
+    pub signed: bool,
+    pub overflow: Overflow,
+}
+
 #[derive(Debug)]
 pub enum Node {
     Number(u32),
     UnaryOperation(UnaryOpcode, Box<Node>),
     BinaryOperation(Box<Node>, BinaryOpcode, Box<Node>),
     Variable(String, Box<Node>),
+    Input(String, Box<Node>),
     Enum(String, Vec<Box<Node>>),
+    Flags(String, Vec<Box<Node>>),
     EnumMember(String, Option<Box<Node>>),
     Type(Type, Vec<Box<Node>>),
     Weighted(Replacement, Vec<Box<Node>>),
+    Except(Box<Node>, Vec<Box<Node>>),
     WeightedSample(u32, Box<Node>),
     RIdentifier(String, VariableMethod),
+    Call(String, Vec<Box<Node>>),
+    Lookup(Lookup, String, Box<Node>),
+    Constraint(String, Vec<Box<Node>>),
+    Markov(Vec<Box<Node>>, Vec<(Box<Node>, Box<Node>)>),
+    NamedArg(String, Box<Node>),
+    Packed(Vec<(String, u32, Box<Node>)>),
+    Width(Width, Box<Node>),
+    DonePolicy(Box<Node>, DonePolicy, DoneReset),
 }
 
 /// An abstraction above Node to implement `import`
@@ -80,9 +181,17 @@ pub enum Item {
 impl fmt::Display for BinaryOpcode {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let operator = match *self {
+            BinaryOpcode::LogicalOr => "||",
+            BinaryOpcode::LogicalAnd => "&&",
             BinaryOpcode::Or => "|",
             BinaryOpcode::Xor => "^",
             BinaryOpcode::And => "&",
+            BinaryOpcode::Eq => "==",
+            BinaryOpcode::Ne => "!=",
+            BinaryOpcode::Lt => "<",
+            BinaryOpcode::Le => "<=",
+            BinaryOpcode::Gt => ">",
+            BinaryOpcode::Ge => ">=",
             BinaryOpcode::Shl => "<<",
             BinaryOpcode::Shr => ">>",
             BinaryOpcode::Add => "+",
@@ -96,11 +205,82 @@ impl fmt::Display for BinaryOpcode {
     }
 }
 
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let policy = match *self {
+            Overflow::Truncate => "truncate",
+            Overflow::Saturate => "saturate",
+            Overflow::Error => "error",
+        };
+
+        write!(f, "{}", policy)
+    }
+}
+
+impl fmt::Display for Width {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", if self.signed { "i" } else { "u" }, self.bits)?;
+        if self.overflow != Overflow::Truncate {
+            write!(f, " {}", self.overflow)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Corruption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let corruption = match *self {
+            Corruption::Flip => "flip",
+            Corruption::Replace => "replace",
+            Corruption::Xor => "xor",
+        };
+
+        write!(f, "{}", corruption)
+    }
+}
+
+impl fmt::Display for Lookup {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let source = match *self {
+            Lookup::Env => "env",
+            Lookup::Arg => "arg",
+        };
+
+        write!(f, "{}", source)
+    }
+}
+
+impl fmt::Display for DonePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let policy = match *self {
+            DonePolicy::All => "all",
+            DonePolicy::Any => "any",
+            DonePolicy::Left => "left",
+            DonePolicy::Right => "right",
+        };
+
+        write!(f, "{}", policy)
+    }
+}
+
+impl fmt::Display for DoneReset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let reset = match *self {
+            DoneReset::Sticky => "sticky",
+            DoneReset::Periodic => "periodic",
+        };
+
+        write!(f, "{}", reset)
+    }
+}
+
 impl fmt::Display for UnaryOpcode {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let operator = match *self {
             UnaryOpcode::Inv => "~",
             UnaryOpcode::Neg => "-",
+            UnaryOpcode::Not => "!",
         };
 
         write!(f, "{}", operator)
@@ -1,4 +1,3 @@
-use std::str::FromStr;
 use std::char;
 use std::path::{Path, MAIN_SEPARATOR};
 use std::fs::File;
@@ -14,10 +13,52 @@ use crate::ast::{
     Node,
     Item,
     Replacement,
+    Overflow,
+    Width,
+    DonePolicy,
+    DoneReset,
+    Corruption,
+    Lookup,
 };
 
 pub use grammar::*;
 
+/// Wraps `node` in an `Except` node if an `except` clause is present
+#[allow(clippy::vec_box)]
+fn except(node: Box<Node>, excluded: Option<Vec<Box<Node>>>) -> Box<Node> {
+    match excluded {
+        Some(excluded) => Box::new(Node::Except(node, excluded)),
+        None => node,
+    }
+}
+
+/// Parses `digits` in `radix` ignoring `_` separators
+fn parse_radix(digits: &str, radix: u32) -> Result<u32, &'static str> {
+    u32::from_str_radix(&digits.replace('_', ""), radix).or(Err("a number that fits in 32 bits"))
+}
+
+/// Multiplies `value` by a size suffix
+fn scale(value: u32, suffix: u64) -> Result<u32, &'static str> {
+    let value = u64::from(value) * suffix;
+
+    if value > u64::from(u32::MAX) {
+        Err("a number that fits in 32 bits")
+    } else {
+        Ok(value as u32)
+    }
+}
+
+/// Checks that `value` fits in `size` bits
+fn sized(size: u32, value: u32) -> Result<u32, &'static str> {
+    if size == 0 || size > 32 {
+        Err("a size in [1, 32]")
+    } else if size < 32 && value >> size != 0 {
+        Err("a number that fits in the size")
+    } else {
+        Ok(value)
+    }
+}
+
 peg::parser!{grammar grammar() for str {
     rule import_path() -> &'input str
         = quiet!{$([':' | 'a'..='z' | 'A'..='Z' | '_']+)} / expected!("import path")
@@ -64,22 +105,73 @@ peg::parser!{grammar grammar() for str {
         = quiet!{$(['A'..='Z'] ['a'..='z' | 'A'..='Z' | '0'..='9']*)} / expected!("type name")
 
     rule dec_digit() = ['0'..='9']
+    rule dec_digits() -> u32
+        = s:$(dec_digit() quiet!{(dec_digit() / "_")*}) {? parse_radix(s, 10) }
     rule dec_number() -> u32
-        = s:$(dec_digit() (dec_digit() / "_")*) {
-            let stripped = &str::replace(s, "_", "");
-            u32::from_str(stripped).unwrap()
-        }
+        = dec_digits()
+
+    rule size_suffix() -> u64
+        = "K" { 1 << 10 }
+        / "M" { 1 << 20 }
+        / "G" { 1 << 30 }
+
+    rule suffixed_number() -> u32
+        = value:dec_digits() suffix:size_suffix() {? scale(value, suffix) }
 
     rule hex_digit() = ['0'..='9' | 'a'..='f' | 'A'..='F']
+    rule hex_digits() -> u32
+        = s:$(hex_digit() quiet!{(hex_digit() / "_")*}) {? parse_radix(s, 16) }
     rule hex_number() -> u32
-        = "0" ['x' | 'X'] s:$(hex_digit() (hex_digit() / "_")*) {
-            let stripped = &str::replace(s, "_", "");
-            u32::from_str_radix(stripped, 16).unwrap()
-        }
+        = "0" ['x' | 'X'] v:hex_digits() { v }
+
+    rule oct_digit() = ['0'..='7']
+    rule oct_digits() -> u32
+        = s:$(oct_digit() quiet!{(oct_digit() / "_")*}) {? parse_radix(s, 8) }
+    rule oct_number() -> u32
+        = "0" ['o' | 'O'] v:oct_digits() { v }
+
+    rule bin_digit() = ['0' | '1']
+    rule bin_digits() -> u32
+        = s:$(bin_digit() quiet!{(bin_digit() / "_")*}) {? parse_radix(s, 2) }
+    rule bin_number() -> u32
+        = "0" ['b' | 'B'] v:bin_digits() { v }
+
+    // A Verilog sized literal E.g. `8'hff`
+    rule sized_number() -> u32
+        = s:sized_literal() { s.1 }
+
+    // The (size, value) of a Verilog sized literal
+    rule sized_literal() -> (u32, u32)
+        = size:dec_digits() "'" value:sized_value() {? sized(size, value).map(|value| (size, value)) }
+
+    rule sized_value() -> u32
+        = ['h' | 'H'] v:hex_digits() { v }
+        / ['d' | 'D'] v:dec_digits() { v }
+        / ['o' | 'O'] v:oct_digits() { v }
+        / ['b' | 'B'] v:bin_digits() { v }
 
     rule number() -> Box<Node>
-        = u:hex_number() { Box::new(Node::Number(u)) }
-        / u:dec_number() { Box::new(Node::Number(u)) }
+        = u:number_value() { Box::new(Node::Number(u)) }
+
+    rule number_value() -> u32
+        = sized_number()
+        / hex_number()
+        / oct_number()
+        / bin_number()
+        / suffixed_number()
+        / dec_number()
+
+    // A number literal surrounded by optional whitespace E.g. the value of an `env()` lookup
+    pub rule number_literal() -> u32
+        = _ u:number_value() _ { u }
+
+    rule function_name() -> &'input str
+        = quiet!{$(['a'..='z' | '_'] ['a'..='z' | 'A'..='Z' | '0'..='9' | '_']*)} / expected!("function name")
+
+    rule call() -> Box<Node>
+        = a:function_name() _ "(" _ b:expr() ** ("," _) _ ")" {
+            Box::new(Node::Call(a.into(), b))
+        }
 
     rule r_identifier() -> Box<Node>
         = a:identifier() b:variable_method_call()? {
@@ -96,23 +188,51 @@ peg::parser!{grammar grammar() for str {
         = "." a:variable_method() { a }
 
     rule variable_method() -> VariableMethod
-        = "prev" { VariableMethod::Prev }
+        = "prev" _ "(" _ n:dec_number() _ ")" { VariableMethod::PrevN(n) }
+        / "prev" { VariableMethod::Prev }
         / "copy" { VariableMethod::Copy }
 
     rule typ() -> Box<Node>
         = pattern()
         / range()
+        / cyclic()
+        / filter()
         / weighted()
+        / markov()
+        / burst()
+        / walk()
+        / near()
+        / bits()
+        / combo()
+        / period()
+        / no_repeat()
+        / ids()
+        / lookup()
+        / alloc()
+        / inject()
+        / packed_type()
         / sequence()
         / done()
         / once()
 
     rule expr() -> Box<Node> = precedence!{
-        x:(@) _ "|" _  y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::Or, y)) }
+        x:(@) _ "||" _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::LogicalOr, y)) }
+        --
+        x:(@) _ "&&" _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::LogicalAnd, y)) }
+        --
+        x:(@) _ "|" !"|" _  y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::Or, y)) }
         --
         x:(@) _ "^" _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::Xor, y)) }
         --
-        x:(@) _ "&" _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::And, y)) }
+        x:(@) _ "&" !"&" _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::And, y)) }
+        --
+        x:(@) _ "==" _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::Eq, y)) }
+        x:(@) _ "!=" _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::Ne, y)) }
+        --
+        x:(@) _ "<=" _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::Le, y)) }
+        x:(@) _ ">=" _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::Ge, y)) }
+        x:(@) _ "<" !"<" _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::Lt, y)) }
+        x:(@) _ ">" !">" _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::Gt, y)) }
         --
         x:(@) _ "<<" _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::Shl, y)) }
         x:(@) _ ">>" _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::Shr, y)) }
@@ -124,26 +244,84 @@ peg::parser!{grammar grammar() for str {
         x:(@) _ "/" _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::Div, y)) }
         x:(@) _ "%" _ y:@ { Box::new(Node::BinaryOperation(x, BinaryOpcode::Mod, y)) }
         --
+        x:@ "." "done" _ "(" _ p:done_policy() r:(_ "," _ r:done_reset() { r })? _ ")" {
+            Box::new(Node::DonePolicy(x, p, r.unwrap_or(DoneReset::Sticky)))
+        }
+        --
         "(" _ v:expr() _ ")" { v }
         "~" _ v:@ { Box::new(Node::UnaryOperation(UnaryOpcode::Inv, v)) }
+        "!" _ v:@ { Box::new(Node::UnaryOperation(UnaryOpcode::Not, v)) }
         "-" _ v:@ { Box::new(Node::UnaryOperation(UnaryOpcode::Neg, v)) }
         v:number() { v }
         v:typ() { v }
+        v:call() { v }
         v:r_identifier() { v }
     }
 
     rule item(import_paths: &mut SourcePaths) -> Item
         = enum()
+        / flags()
+        / packed()
+        / constraint()
+        / input()
         / variable()
         / import(import_paths)
 
+    rule input() -> Item
+        = "input" !['a'..='z' | 'A'..='Z' | '0'..='9' | '_' | ':'] _ lhs:field_name() _ "=" _ rhs:expr() _ ";" {
+            Item::Single(
+                Box::new(Node::Input(lhs.into(), rhs))
+            )
+        }
+
     rule variable() -> Item
-        = lhs:identifier() _ "=" _ rhs:expr() _ ";" {
+        = lhs:field_name() _ ":" !":" _ w:width() _ "=" _ rhs:expr() _ ";" {
+            Item::Single(
+                Box::new(Node::Variable(lhs.into(), Box::new(Node::Width(w, rhs))))
+            )
+        }
+        // A sized literal gives the variable its width E.g. `a = 8'hff;` is `a: u8 = 0xff;`
+        / lhs:identifier() _ "=" _ s:sized_literal() _ ";" {
+            let width = Width {
+                bits: s.0,
+                signed: false,
+                overflow: Overflow::Truncate,
+            };
+
+            Item::Single(
+                Box::new(Node::Variable(lhs.into(), Box::new(Node::Width(width, Box::new(Node::Number(s.1))))))
+            )
+        }
+        / lhs:identifier() _ "=" _ rhs:expr() _ ";" {
             Item::Single(
                 Box::new(Node::Variable(lhs.into(), rhs))
             )
         }
 
+    rule width() -> Width
+        = signed:("u" { false } / "i" { true }) bits:dec_number() overflow:(_ a:overflow() { a })? {
+            Width {
+                bits,
+                signed,
+                overflow: overflow.unwrap_or(Overflow::Truncate),
+            }
+        }
+
+    rule done_policy() -> DonePolicy
+        = "all" { DonePolicy::All }
+        / "any" { DonePolicy::Any }
+        / "left" { DonePolicy::Left }
+        / "right" { DonePolicy::Right }
+
+    rule done_reset() -> DoneReset
+        = "sticky" { DoneReset::Sticky }
+        / "periodic" { DoneReset::Periodic }
+
+    rule overflow() -> Overflow
+        = "truncate" { Overflow::Truncate }
+        / "saturate" { Overflow::Saturate }
+        / "error" { Overflow::Error }
+
     pub rule items(import_paths: &mut SourcePaths) -> Vec<Item>
         = _ a:item(import_paths) ** _ _ { a }
 
@@ -157,8 +335,50 @@ peg::parser!{grammar grammar() for str {
             )
         }
 
+    rule constraint() -> Item
+        = "constraint" _ id:type_name() _ "{" _ members:constraint_member() ** _ _ "}" {
+            Item::Single(
+                Box::new(Node::Constraint(id.into(), members))
+            )
+        }
+
+    rule constraint_member() -> Box<Node>
+        = lhs:identifier() _ "=" !"=" _ rhs:expr() _ ";" {
+            Box::new(Node::Variable(lhs.into(), rhs))
+        }
+        / a:expr() _ ";" { a }
+
+    rule flags() -> Item
+        = "flags" _ id:type_name() _ "{" _ members:enum_member() ** ("," _) optional_trailing_comma() _ "}" {
+            Item::Single(
+                Box::new(Node::Flags(id.into(), members))
+            )
+        }
+
+    rule packed() -> Item
+        = "packed" _ id:type_name() _ "{" _ a:packed_fields() _ "}" {
+            Item::Single(
+                Box::new(Node::Variable(id.into(), Box::new(Node::Packed(a))))
+            )
+        }
+
+    rule packed_type() -> Box<Node>
+        = "Packed" _ "{" _ a:packed_fields() _ "}" {
+            Box::new(Node::Packed(a))
+        }
+
+    rule packed_fields() -> Vec<(String, u32, Box<Node>)>
+        = a:packed_field() ++ ("," _) optional_trailing_comma() { a }
+
+    rule packed_field() -> (String, u32, Box<Node>)
+        = a:field_name() _ ":" _ b:dec_number() _ "=" _ c:expr() { (a.into(), b, c) }
+
+    rule field_name() -> &'input str
+        = quiet!{$(['a'..='z' | 'A'..='Z' | '_'] ['a'..='z' | 'A'..='Z' | '0'..='9' | '_']*)} / expected!("field name")
+
     rule enum_assignment() -> Box<Node>
         = "=" _ a:number() _ { a }
+        / "=" _ "-" _ a:number() _ { Box::new(Node::UnaryOperation(UnaryOpcode::Neg, a)) }
 
     rule enum_member() -> Box<Node>
         = a:type_name() _ b:enum_assignment()? {
@@ -171,7 +391,7 @@ peg::parser!{grammar grammar() for str {
         }
 
     rule sequence() -> Box<Node>
-        = "Sequence" _ "(" _ a:expr() **<1, 3> ("," _) optional_trailing_comma() _ ")" {
+        = "Sequence" _ "(" _ a:arg() **<1, 5> ("," _) optional_trailing_comma() _ ")" {
             Box::new(Node::Type(Type::Sequence, a))
         }
 
@@ -181,8 +401,103 @@ peg::parser!{grammar grammar() for str {
         }
 
     rule range() -> Box<Node>
-        = "[" _ a:expr() **<2> ("," _) _ "]" {
-            Box::new(Node::Type(Type::Range, a))
+        = "[" _ a:expr() **<2> ("," _) _ "]" b:except()? {
+            except(Box::new(Node::Type(Type::Range, a)), b)
+        }
+
+    rule except() -> Vec<Box<Node>>
+        = _ "except" _ "{" _ a:expr() ++ ("," _) optional_trailing_comma() _ "}" { a }
+
+    rule cyclic() -> Box<Node>
+        = "Cyclic" _ "(" _ "[" _ a:expr() **<2> ("," _) _ "]" _ ")" {
+            Box::new(Node::Type(Type::Cyclic, a))
+        }
+
+    rule filter() -> Box<Node>
+        = "Where" _ "(" _ a:expr() **<2, 3> ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::Where, a))
+        }
+
+    rule arg() -> Box<Node>
+        = a:identifier() _ "=" !"=" _ b:expr() { Box::new(Node::NamedArg(a.into(), b)) }
+        / expr()
+
+    rule burst() -> Box<Node>
+        = "Burst" _ "(" _ a:arg() ++ ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::Burst, a))
+        }
+
+    rule walk() -> Box<Node>
+        = "Walk" _ "(" _ a:arg() ++ ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::Walk, a))
+        }
+
+    rule near() -> Box<Node>
+        = "Near" _ "(" _ a:arg() ++ ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::Near, a))
+        }
+
+    rule bits() -> Box<Node>
+        = a:bits_type() _ "(" _ b:arg() ++ ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(a, b))
+        }
+
+    rule bits_type() -> Type
+        = "WalkingOnes" { Type::WalkingOnes }
+        / "WalkingZeros" { Type::WalkingZeros }
+        / "Gray" { Type::Gray }
+        / "Lfsr" { Type::Lfsr }
+        / "Checkerboard" { Type::Checkerboard }
+        / "RandomBits" { Type::RandomBits }
+
+    rule period() -> Box<Node>
+        = a:period_type() _ "(" _ b:arg() ++ ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(a, b))
+        }
+
+    rule period_type() -> Type
+        = "Repeat" { Type::Repeat }
+        / "Take" { Type::Take }
+        / "Skip" { Type::Skip }
+        / "Cycle" { Type::Cycle }
+
+    rule no_repeat() -> Box<Node>
+        = "NoRepeat" _ "(" _ a:arg() ++ ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::NoRepeat, a))
+        }
+
+    rule ids() -> Box<Node>
+        = "Ids" _ "(" _ a:arg() ++ ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::Ids, a))
+        }
+
+    rule alloc() -> Box<Node>
+        = "Alloc" _ "(" _ a:arg() ++ ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::Alloc, a))
+        }
+
+    rule inject() -> Box<Node>
+        = "Inject" _ "(" _ a:expr() _ "," _ b:expr() _ "," _ c:corruption() _ "(" _ d:expr() _ ")" optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::Inject(c), vec![a, b, d]))
+        }
+
+    rule corruption() -> Corruption
+        = "flip" { Corruption::Flip }
+        / "replace" { Corruption::Replace }
+        / "xor" { Corruption::Xor }
+
+    rule lookup() -> Box<Node>
+        = a:lookup_source() _ "(" _ b:string() _ "," _ c:expr() optional_trailing_comma() _ ")" {
+            Box::new(Node::Lookup(a, b, c))
+        }
+
+    rule lookup_source() -> Lookup
+        = "env" { Lookup::Env }
+        / "arg" { Lookup::Arg }
+
+    rule combo() -> Box<Node>
+        = "Combo" _ "(" _ a:arg() ++ ("," _) optional_trailing_comma() _ ")" {
+            Box::new(Node::Type(Type::Combo, a))
         }
 
     rule weighted_sample() -> Box<Node>
@@ -198,14 +513,25 @@ peg::parser!{grammar grammar() for str {
         = a:dec_number() _ ":" _ { a }
 
     rule weighted() -> Box<Node>
-        = replacement:"r"?"{" _ entries:(weighted_sample() / expand()) ++ ("," _) optional_trailing_comma() _ "}" {
+        = replacement:"r"?"{" _ entries:(expand() / weighted_sample()) ++ ("," _) optional_trailing_comma() _ "}" b:except()? {
             let replacement = match replacement {
                 Some(_) => Replacement::With,
                 None => Replacement::Without,
             };
-            Box::new(Node::Weighted(replacement, entries))
+            except(Box::new(Node::Weighted(replacement, entries)), b)
+        }
+
+    rule markov() -> Box<Node>
+        = "Markov" _ a:markov_args()? _ "{" _ b:markov_transition() ++ ("," _) optional_trailing_comma() _ "}" {
+            Box::new(Node::Markov(a.unwrap_or_default(), b))
         }
 
+    rule markov_args() -> Vec<Box<Node>>
+        = "(" _ a:expr() **<1, 2> ("," _) optional_trailing_comma() _ ")" { a }
+
+    rule markov_transition() -> (Box<Node>, Box<Node>)
+        = a:expr() _ "=>" _ b:expr() { (a, b) }
+
     rule done() -> Box<Node>
         = "Done" _ "(" _ a:expr() _ ")" {
             Box::new(Node::Type(Type::Done, vec![a]))
@@ -7,6 +7,6 @@ pub mod ast;
 pub mod error;
 
 pub use searchpath::SearchPath;
-pub use parser::Parser;
+pub use parser::{parse_number, Parser};
 pub use error::Error;
 pub use error::ParseError;
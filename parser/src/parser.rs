@@ -4,6 +4,15 @@ use crate::searchpath::SearchPath;
 use crate::sourcepaths::SourcePaths;
 use crate::error::{Error, ParseError, Result};
 
+/// Parses a number literal using the syntax of numbers in expressions
+///
+/// E.g. `64`, `0x40`, `4K`, or `8'h40`.  Leading and trailing whitespace is ignored.
+///
+/// Returns `None` if `s` is not a number literal.
+pub fn parse_number(s: &str) -> Option<u32> {
+    grammar::number_literal(s).ok()
+}
+
 pub struct Parser {
     searchpath: SearchPath,
 }
@@ -25,3 +25,65 @@ fn operations() {
         parse("a = 1+2*3;"),
         "[Variable(\"a\", BinaryOperation(Number(1), Add, BinaryOperation(Number(2), Mul, Number(3))))]");
 }
+
+#[test]
+fn markov() {
+    assert_eq!(
+        parse("a = Markov { 0 => 1, 1 => 0, };"),
+        "[Variable(\"a\", Markov([], [(Number(0), Number(1)), (Number(1), Number(0))]))]"
+    );
+    assert_eq!(
+        parse("a = Markov(1, 2) { 0 => 1 };"),
+        "[Variable(\"a\", Markov([Number(1), Number(2)], [(Number(0), Number(1))]))]"
+    );
+    assert!(parse_result("a = Markov {};").is_err());
+}
+
+#[test]
+fn named_args() {
+    assert_eq!(
+        parse("a = Burst(1, off = 2, active = b == 1, idle = 0);"),
+        "[Variable(\"a\", Type(Burst, [Number(1), NamedArg(\"off\", Number(2)), NamedArg(\"active\", BinaryOperation(RIdentifier(\"b\", Next), Eq, Number(1))), NamedArg(\"idle\", Number(0))]))]"
+    );
+}
+
+#[test]
+fn done_policy() {
+    assert_eq!(
+        parse("a = (1 + 2).done(any, periodic) * 3;"),
+        "[Variable(\"a\", BinaryOperation(DonePolicy(BinaryOperation(Number(1), Add, Number(2)), Any, Periodic), Mul, Number(3)))]"
+    );
+}
+
+#[test]
+fn inject() {
+    assert_eq!(
+        parse("a = Inject(b, 10, flip(1));"),
+        "[Variable(\"a\", Type(Inject(Flip), [RIdentifier(\"b\", Next), Number(10), Number(1)]))]"
+    );
+    assert_eq!(
+        parse("a = Inject(1, 2, replace(3));"),
+        "[Variable(\"a\", Type(Inject(Replace), [Number(1), Number(2), Number(3)]))]"
+    );
+    assert!(parse_result("a = Inject(1, 2, negate(3));").is_err());
+}
+
+#[test]
+fn call() {
+    assert_eq!(
+        parse("a = [1, mem_free()];"),
+        "[Variable(\"a\", Type(Range, [Number(1), Call(\"mem_free\", [])]))]"
+    );
+    assert_eq!(
+        parse("a = f(b, 2) + c;"),
+        "[Variable(\"a\", BinaryOperation(Call(\"f\", [RIdentifier(\"b\", Next), Number(2)]), Add, RIdentifier(\"c\", Next)))]"
+    );
+}
+
+#[test]
+fn lookup() {
+    assert_eq!(
+        parse("a = env(\"MAX_LEN\", 64) + arg(\"max_len\", b);"),
+        "[Variable(\"a\", BinaryOperation(Lookup(Env, \"MAX_LEN\", Number(64)), Add, Lookup(Arg, \"max_len\", RIdentifier(\"b\", Next))))]"
+    );
+}
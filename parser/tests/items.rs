@@ -18,3 +18,52 @@ fn ast() {
         "[Variable(\"a\", Number(5)), Variable(\"b\", Number(6))]"
     );
 }
+
+#[test]
+fn constraint() {
+    assert_eq!(
+        parse("constraint Xfer { a = [0, 3]; b = 1; a == b; }"),
+        "[Constraint(\"Xfer\", [Variable(\"a\", Type(Range, [Number(0), Number(3)])), Variable(\"b\", Number(1)), BinaryOperation(RIdentifier(\"a\", Next), Eq, RIdentifier(\"b\", Next))])]"
+    );
+}
+
+#[test]
+fn flags() {
+    assert_eq!(
+        parse("flags Perm { R, W = 4 }"),
+        "[Flags(\"Perm\", [EnumMember(\"R\", None), EnumMember(\"W\", Some(Number(4)))])]"
+    );
+}
+
+#[test]
+fn negative_enum_member() {
+    assert_eq!(
+        parse("enum Delta { Back = -1, Stay }"),
+        "[Enum(\"Delta\", [EnumMember(\"Back\", Some(UnaryOperation(Neg, Number(1)))), EnumMember(\"Stay\", None)])]"
+    );
+}
+
+#[test]
+fn packed() {
+    assert_eq!(
+        parse("packed Hdr { op: 4 = 1, len: 12 = [1, 2], }"),
+        "[Variable(\"Hdr\", Packed([(\"op\", 4, Number(1)), (\"len\", 12, Type(Range, [Number(1), Number(2)]))]))]"
+    );
+}
+
+#[test]
+fn width() {
+    assert_eq!(
+        parse("a: u5 = 1; b:i8 saturate = 2;"),
+        "[Variable(\"a\", Width(Width { bits: 5, signed: false, overflow: Truncate }, Number(1))), \
+         Variable(\"b\", Width(Width { bits: 8, signed: true, overflow: Saturate }, Number(2)))]"
+    );
+}
+
+#[test]
+fn input() {
+    assert_eq!(
+        parse("input depth = 0; inputs = 1;"),
+        "[Input(\"depth\", Number(0)), Variable(\"inputs\", Number(1))]"
+    );
+}
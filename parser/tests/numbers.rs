@@ -14,9 +14,15 @@ mod number {
 
     #[test]
     fn bad() {
-        assert!(parse_result("a = 0b0;").is_err());
+        assert!(parse_result("a = 0c0;").is_err());
         assert!(parse_result("a = 1z;").is_err());
     }
+
+    #[test]
+    fn overflow() {
+        assert!(parse_result("a = 4294967295;").is_ok());
+        assert!(parse_result("a = 4294967296;").is_err());
+    }
 }
 
 mod hex_number {
@@ -37,3 +43,98 @@ mod hex_number {
         assert!(parse_result("a = 0X_A5;").is_err());
     }
 }
+
+mod bin_number {
+    use super::*;
+
+    #[test]
+    fn good() {
+        assert_eq!(parse("a = 0b1010;"), "[Variable(\"a\", Number(10))]");
+        assert!(parse_result("a = 0B1_0;").is_ok());
+    }
+
+    #[test]
+    fn bad() {
+        assert!(parse_result("a = 0b2;").is_err());
+        assert!(parse_result("a = 0b_1;").is_err());
+        assert!(parse_result("a = 0b1_0000_0000_0000_0000_0000_0000_0000_0000;").is_err());
+    }
+}
+
+mod oct_number {
+    use super::*;
+
+    #[test]
+    fn good() {
+        assert_eq!(parse("a = 0o17;"), "[Variable(\"a\", Number(15))]");
+        assert!(parse_result("a = 0O1_7;").is_ok());
+    }
+
+    #[test]
+    fn bad() {
+        assert!(parse_result("a = 0o8;").is_err());
+        assert!(parse_result("a = 0o40000000000;").is_err());
+    }
+}
+
+mod suffixed_number {
+    use super::*;
+
+    #[test]
+    fn good() {
+        assert_eq!(
+            parse("a = 4K; b = 1M; c = 2G;"),
+            "[Variable(\"a\", Number(4096)), Variable(\"b\", Number(1048576)), Variable(\"c\", Number(2147483648))]"
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        assert!(parse_result("a = 3G;").is_ok());
+        assert!(parse_result("a = 4G;").is_err());
+    }
+}
+
+mod sized_number {
+    use super::*;
+
+    #[test]
+    fn good() {
+        assert_eq!(
+            parse("a = 8'hFF + 4'b1010; b = [6'o77, 12'd4095];"),
+            "[Variable(\"a\", BinaryOperation(Number(255), Add, Number(10))), Variable(\"b\", Type(Range, [Number(63), Number(4095)]))]"
+        );
+        assert!(parse_result("a = 32'hffff_ffff;").is_ok());
+    }
+
+    #[test]
+    fn width() {
+        assert_eq!(
+            parse("a = 4'b1010; b: u8 = 4'b1010;"),
+            "[Variable(\"a\", Width(Width { bits: 4, signed: false, overflow: Truncate }, Number(10))), \
+             Variable(\"b\", Width(Width { bits: 8, signed: false, overflow: Truncate }, Number(10)))]"
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        let error = parse_result("a = 4'hff;").unwrap_err().to_string();
+        assert!(error.contains("expected a number that fits in the size"));
+
+        let error = parse_result("a = 33'h0;").unwrap_err().to_string();
+        assert!(error.contains("expected a size in [1, 32]"));
+
+        assert!(parse_result("a = 0'h0;").is_err());
+    }
+}
+
+#[test]
+fn parse_number() {
+    assert_eq!(rvs_parser::parse_number(" 0x10 "), Some(16));
+    assert_eq!(rvs_parser::parse_number("4K"), Some(4096));
+    assert_eq!(rvs_parser::parse_number("8'hff"), Some(255));
+    assert_eq!(rvs_parser::parse_number("0b101"), Some(5));
+    assert_eq!(rvs_parser::parse_number("ten"), None);
+    assert_eq!(rvs_parser::parse_number("1 2"), None);
+    assert_eq!(rvs_parser::parse_number(""), None);
+}
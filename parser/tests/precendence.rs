@@ -26,3 +26,19 @@ fn unary_neg() {
         "[Variable(\"a\", UnaryOperation(Neg, Number(1)))]"
     );
 }
+
+#[test]
+fn comparison_and_logical() {
+    assert_eq!(
+        parse("a = 1 << 2 < 3 == 4 & 5 | 6 && 7 || 8;"),
+        "[Variable(\"a\", BinaryOperation(BinaryOperation(BinaryOperation(BinaryOperation(BinaryOperation(BinaryOperation(BinaryOperation(Number(1), Shl, Number(2)), Lt, Number(3)), Eq, Number(4)), And, Number(5)), Or, Number(6)), LogicalAnd, Number(7)), LogicalOr, Number(8)))]"
+    );
+}
+
+#[test]
+fn unary_not() {
+    assert_eq!(
+        parse("a = !1 != 2;"),
+        "[Variable(\"a\", BinaryOperation(UnaryOperation(Not, Number(1)), Ne, Number(2)))]"
+    );
+}
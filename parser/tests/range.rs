@@ -8,3 +8,11 @@ fn ast() {
         "[Variable(\"a\", Type(Range, [Number(1), Number(2)]))]"
     );
 }
+
+#[test]
+fn except() {
+    assert_eq!(
+        parse("a = [1,4] except {2, [3, 3]};"),
+        "[Variable(\"a\", Except(Type(Range, [Number(1), Number(4)]), [Number(2), Type(Range, [Number(3), Number(3)])]))]"
+    );
+}
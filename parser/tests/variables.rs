@@ -20,3 +20,11 @@ fn bad() {
 fn with_enum() {
     assert!(parse_result("a = Enum::Value;").is_ok());
 }
+
+#[test]
+fn prev_n() {
+    assert_eq!(
+        parse("b = a.prev(3);"),
+        "[Variable(\"b\", RIdentifier(\"a\", PrevN(3)))]"
+    );
+}
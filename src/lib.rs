@@ -7,15 +7,26 @@ mod types;
 pub use rvs_parser::SearchPath;
 pub use crate::parser::Parser;
 pub use crate::transform::{Seed, Transform};
-pub use crate::model::{Model, Variable};
+pub use crate::model::{Callback, Function, Model, Variable};
 
 pub use crate::error::{Error, Result};
 
 pub fn parse(search_path: &SearchPath, s: &str) -> Result<Model> {
+    parse_with_args(search_path, s, &[])
+}
+
+/// Parses and transforms `s` with values for `arg("<key>", <default>)` lookups
+///
+/// E.g. `parse_with_args(&Default::default(), "a = arg(\"max_len\", 64);", &[("max_len", "128")])`
+pub fn parse_with_args(search_path: &SearchPath, s: &str, args: &[(&str, &str)]) -> Result<Model> {
     let mut parser = Parser::new(search_path);
     parser.parse(s)?;
 
     let mut transform = Transform::new(Default::default());
+    for &(key, value) in args {
+        transform.set_arg(key, value);
+    }
+
     let mut model = Model::new();
     transform.transform(&mut model, parser.ast())?;
     Ok(model)
@@ -28,6 +28,37 @@ pub trait Expr: fmt::Display + ExprClone {
         self.data().done
     }
 
+    /// Sets the value returned by the next call to `next`
+    ///
+    /// Returns false if the expression is not set by the host.
+    fn set(&mut self, _value: u32) -> bool {
+        false
+    }
+
+    /// Returns a value previously returned by `next` to the expression
+    ///
+    /// Returns false if the expression does not support release or the value is not in use.
+    fn release(&mut self, _value: u32) -> bool {
+        false
+    }
+
+    /// Returns the length of the region returned by the most recent call to `next`
+    ///
+    /// Returns 0 if the expression does not return regions.
+    fn region_len(&self) -> u32 {
+        0
+    }
+
+    /// Returns whether the value returned by the most recent call to `next` was corrupted
+    fn injected(&self) -> bool {
+        false
+    }
+
+    /// Returns whether the most recent call to `next` found no value to return
+    fn exhausted(&self) -> bool {
+        false
+    }
+
     fn data(&self) -> &ExprData;
 }
 
@@ -0,0 +1,40 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+/// A host function called with the values of its arguments
+pub type Callback = Box<dyn FnMut(&[u32]) -> u32>;
+
+/// A host function registered with `Model::register_fn`
+#[derive(Clone)]
+pub struct Function {
+    arity: usize,
+    callback: Rc<RefCell<Callback>>,
+}
+
+impl Function {
+    pub fn new(arity: usize, callback: Callback) -> Function {
+        Function {
+            arity,
+            callback: Rc::new(RefCell::new(callback)),
+        }
+    }
+
+    /// Returns the number of arguments of the function
+    pub fn arity(&self) -> usize {
+        self.arity
+    }
+
+    /// # Panics
+    ///
+    /// * If the function is called from within itself
+    pub fn call(&self, args: &[u32]) -> u32 {
+        (self.callback.borrow_mut())(args)
+    }
+}
+
+impl fmt::Debug for Function {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Function {{ arity: {} }}", self.arity)
+    }
+}
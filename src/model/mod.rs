@@ -1,8 +1,10 @@
 mod expr;
 mod variable;
+mod function;
 #[cfg_attr(feature = "cargo-clippy", allow(module_inception))]
 mod model;
 
 pub use self::model::Model;
 pub use self::variable::{Variable, VariableRef, VariableWeak};
 pub use self::expr::{Expr, ExprData};
+pub use self::function::{Callback, Function};
@@ -1,4 +1,4 @@
-use super::VariableRef;
+use super::{Callback, Function, VariableRef};
 
 use indexmap::{map::Entry, IndexMap};
 use std::fmt;
@@ -8,6 +8,8 @@ pub struct Model {
     variables: Vec<VariableRef>,
     variable_indexes: IndexMap<String, usize>,
     most_recent: usize,
+    functions: IndexMap<String, Function>,
+    warnings: Vec<String>,
 }
 
 impl Model {
@@ -42,6 +44,26 @@ impl Model {
         }
     }
 
+    /// Registers a host function that expressions call as `<name>(<args>)`
+    ///
+    /// Functions must be registered before the model is transformed.  The callback is called on
+    /// every draw with the values of its `arity` arguments.  If the function `name` already
+    /// exists, the pre-existing function is replaced for expressions transformed afterwards.
+    pub fn register_fn(&mut self, name: &str, arity: usize, callback: Callback) {
+        self.add_function(name, Function::new(arity, callback));
+    }
+
+    /// Registers a host function shared with other models
+    ///
+    /// Same as `register_fn` except that the callback is shared with every clone of `function`.
+    pub fn add_function(&mut self, name: &str, function: Function) {
+        self.functions.insert(name.into(), function);
+    }
+
+    pub fn get_function(&self, name: &str) -> Option<&Function> {
+        self.functions.get(name)
+    }
+
     pub fn get_variable_index(&self, name: &str) -> Option<usize> {
         let index = self.variable_indexes.get(name)?;
         Some(*index)
@@ -58,6 +80,44 @@ impl Model {
         Some(variable)
     }
 
+    /// Sets the value of the input `name`
+    ///
+    /// E.g. `input depth = 0;`
+    ///
+    /// Returns false if the variable does not exist or is not an input.
+    pub fn set_input(&self, name: &str, value: u32) -> bool {
+        match self.get_variable_by_name(name) {
+            Some(variable) => variable.borrow_mut().set(value),
+            None => false,
+        }
+    }
+
+    /// Returns `value` to the variable `name` so that it may be returned by `next` again
+    ///
+    /// E.g. frees an ID returned by `Ids(lo, hi)` or a region returned by `Alloc(base, size, len,
+    /// align)`.
+    ///
+    /// Returns false if the variable does not exist, does not support release, or the value is
+    /// not in use.
+    pub fn release(&self, name: &str, value: u32) -> bool {
+        match self.get_variable_by_name(name) {
+            Some(variable) => variable.borrow_mut().release(value),
+            None => false,
+        }
+    }
+
+    /// Records a problem that did not prevent the transform
+    pub fn add_warning(&mut self, warning: String) {
+        self.warnings.push(warning);
+    }
+
+    /// Returns the warnings of every transform into the model in order
+    ///
+    /// E.g. a constant Packed field value that does not fit in its field was truncated.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
     pub fn get_most_recently_added(&self) -> Option<&VariableRef> {
         self.variables.get(self.most_recent)
     }
@@ -73,8 +133,16 @@ impl Model {
 impl fmt::Display for Model {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         for (name, variable) in self.variables_iter() {
-            write!(f, "{} = ", name)?;
-            variable.borrow().fmt(f)?;
+            let variable = variable.borrow();
+            if variable.is_input() {
+                write!(f, "input ")?;
+            }
+            write!(f, "{}", name)?;
+            if let Some(width) = variable.width_annotation() {
+                write!(f, ": {}", width)?;
+            }
+            write!(f, " = ")?;
+            variable.fmt(f)?;
             writeln!(f, ";")?;
         }
 
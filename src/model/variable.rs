@@ -1,13 +1,25 @@
 use super::expr::Expr;
 use crate::transform::CrateRng;
+use crate::types::width_mask;
+
+use rvs_parser::ast::{Overflow, Width};
 
 use std::fmt;
 use std::rc::{Rc, Weak};
 use std::cell::RefCell;
+use std::collections::VecDeque;
 
 pub struct Variable {
     expr: Box<dyn Expr>,
-    rng: CrateRng,
+    /// The RNG of the variable.  `None` for inputs.
+    rng: Option<CrateRng>,
+    width: Option<Width>,
+
+    /// The most recent values, most recent first
+    history: VecDeque<u32>,
+    history_depth: usize,
+    /// Whether the most recent value did not fit under the `error` overflow policy
+    overflowed: bool,
 }
 
 pub type VariableRef = Rc<RefCell<Box<Variable>>>;
@@ -15,20 +27,206 @@ pub type VariableWeak = Weak<RefCell<Box<Variable>>>;
 
 impl Variable {
     pub fn new(expr: Box<dyn Expr>, rng: CrateRng) -> Variable {
-        Variable { expr, rng }
+        Variable {
+            expr,
+            rng: Some(rng),
+            width: None,
+            history: VecDeque::new(),
+            history_depth: 1,
+            overflowed: false,
+        }
+    }
+
+    /// Returns a variable whose value is set by the host with `set`
+    pub fn new_input(expr: Box<dyn Expr>) -> Variable {
+        Variable {
+            expr,
+            rng: None,
+            width: None,
+            history: VecDeque::new(),
+            history_depth: 1,
+            overflowed: false,
+        }
+    }
+
+    /// Sets the number of past values returned by `history`
+    ///
+    /// The depth is at least 1.
+    pub fn set_history_depth(&mut self, depth: usize) {
+        self.history_depth = depth.max(1);
+        self.history.truncate(self.history_depth);
+    }
+
+    pub fn history_depth(&self) -> usize {
+        self.history_depth
+    }
+
+    /// Returns the `n`th most recent value
+    ///
+    /// `history(1)` is the same as `prev()`.
+    ///
+    /// # Errors
+    ///
+    /// * Returns 0 if `n` is 0 or greater than the history depth
+    /// * Returns 0 if fewer than `n` values have been returned by `next`
+    pub fn history(&self, n: usize) -> u32 {
+        if n == 1 {
+            self.prev()
+        } else if n == 0 || n > self.history_depth {
+            0
+        } else {
+            self.history.get(n - 1).cloned().unwrap_or(0)
+        }
+    }
+
+    /// Limits all values of the variable to `width`
+    pub fn set_width(&mut self, width: Width) {
+        self.width = Some(width);
+    }
+
+    /// Returns the width annotation of the variable if any
+    pub fn width_annotation(&self) -> Option<Width> {
+        self.width
+    }
+
+    /// Returns the declared width of the variable in bits
+    ///
+    /// Variables without a width annotation are 32 bits wide.
+    pub fn width(&self) -> u32 {
+        self.width.map_or(32, |width| width.bits)
     }
 
     pub fn clone_expr(&self) -> Box<dyn Expr> {
         self.expr.clone()
     }
 
+    /// Returns whether the variable is declared signed
+    pub fn signed(&self) -> bool {
+        self.width.is_some_and(|width| width.signed)
+    }
+
+    /// Returns the next value limited to the declared width
+    ///
+    /// The `error` overflow policy truncates a value that does not fit and sets `overflowed`.
     #[cfg_attr(feature = "cargo-clippy", allow(should_implement_trait))]
     pub fn next(&mut self) -> u32 {
-        self.expr.next(&mut self.rng)
+        let value = match self.rng {
+            Some(ref mut rng) => self.expr.next(rng),
+            None => self.expr.prev(),
+        };
+        let value = self.checked_limit(value);
+
+        self.history.push_front(value);
+        self.history.truncate(self.history_depth);
+
+        value
+    }
+
+    fn checked_limit(&mut self, value: u32) -> u32 {
+        match self.width {
+            Some(width) => {
+                let limited = limit(width, value);
+                self.overflowed = width.overflow == Overflow::Error && limited != value;
+
+                limited
+            }
+            None => value,
+        }
+    }
+
+    /// Returns whether `value` fits in the declared width without truncation or saturation
+    ///
+    /// Signed values must be sign extended to 32 bits.
+    pub fn fits(&self, value: u32) -> bool {
+        self.width.is_none_or(|width| limit(width, value) == value)
+    }
+
+    /// Returns whether the value returned by the last call to `next` did not fit in the declared
+    /// width
+    ///
+    /// Only set under the `error` overflow policy.  The value was truncated.
+    pub fn overflowed(&self) -> bool {
+        self.overflowed
     }
 
+    /// Returns the previous value limited to the declared width
     pub fn prev(&self) -> u32 {
-        self.expr.prev()
+        let value = self.expr.prev();
+
+        match self.width {
+            Some(width) => limit(width, value),
+            None => value,
+        }
+    }
+
+    /// Returns the next value as a two's complement signed value
+    pub fn next_i32(&mut self) -> i32 {
+        self.next() as i32
+    }
+
+    /// Returns the previous value as a two's complement signed value
+    pub fn prev_i32(&self) -> i32 {
+        self.prev() as i32
+    }
+
+    /// Returns the next value sign extended if the variable is signed, zero extended otherwise
+    pub fn next_i64(&mut self) -> i64 {
+        let value = self.next();
+        self.extend(value)
+    }
+
+    /// Returns the previous value sign extended if the variable is signed, zero extended
+    /// otherwise
+    pub fn prev_i64(&self) -> i64 {
+        self.extend(self.prev())
+    }
+
+    fn extend(&self, value: u32) -> i64 {
+        if self.signed() {
+            i64::from(value as i32)
+        } else {
+            i64::from(value)
+        }
+    }
+
+    /// Returns whether the value of the variable is set by the host
+    pub fn is_input(&self) -> bool {
+        self.rng.is_none()
+    }
+
+    /// Sets the value of an input
+    ///
+    /// Dependent expressions read the value on their next draw.
+    ///
+    /// Returns false if the variable is not an input.
+    pub fn set(&mut self, value: u32) -> bool {
+        self.is_input() && self.expr.set(value)
+    }
+
+    /// Returns a value to the variable so that it may be returned by `next` again
+    ///
+    /// Returns false if the variable does not support release or the value is not in use.
+    pub fn release(&mut self, value: u32) -> bool {
+        self.expr.release(value)
+    }
+
+    /// Returns the length of the region starting at `prev()`
+    ///
+    /// Returns 0 if the variable does not return regions.  E.g. `Alloc(base, size, len, align)`.
+    pub fn region_len(&self) -> u32 {
+        self.expr.region_len()
+    }
+
+    /// Returns whether `prev()` was corrupted by `Inject(expr, percent, corruption)`
+    pub fn injected(&self) -> bool {
+        self.expr.injected()
+    }
+
+    /// Returns whether the last call to `next` found every value in use
+    ///
+    /// `prev()` is not a new value when this is true.  E.g. `Ids(lo, hi)`.
+    pub fn exhausted(&self) -> bool {
+        self.expr.exhausted()
     }
 
     pub fn done(&self) -> bool {
@@ -36,6 +234,26 @@ impl Variable {
     }
 }
 
+/// Limits `value` to `width`
+///
+/// Signed values are sign extended to 32 bits.
+fn limit(width: Width, value: u32) -> u32 {
+    let mask = width_mask(width.bits);
+
+    match (width.signed, width.overflow) {
+        (false, Overflow::Saturate) => value.min(mask),
+        (false, _) => value & mask,
+        (true, Overflow::Saturate) => {
+            let max = (mask >> 1) as i32;
+            (value as i32).max(!max).min(max) as u32
+        }
+        (true, _) => {
+            let shift = 32 - width.bits;
+            (((value << shift) as i32) >> shift) as u32
+        }
+    }
+}
+
 impl fmt::Display for Variable {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         self.expr.fmt(f)
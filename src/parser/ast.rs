@@ -24,7 +24,7 @@ impl Ast {
 
     /// Adds AST nodes to the AST.
     ///
-    /// * If Node is a variable definition
+    /// * If Node is a variable or input definition
     ///
     ///   * If variable has been previously defined
     ///
@@ -40,17 +40,12 @@ impl Ast {
     }
 
     fn add_node(&mut self, node: Box<ast::Node>) {
-        let is_variable = if let ast::Node::Variable(_, _) = *node {
-            true
-        } else {
-            false
-        };
+        let is_variable = matches!(*node, ast::Node::Variable(_, _) | ast::Node::Input(_, _));
 
         if is_variable {
-            let name = if let ast::Node::Variable(ref name, _) = *node {
-                name.to_owned()
-            } else {
-                "".to_owned()
+            let name = match *node {
+                ast::Node::Variable(ref name, _) | ast::Node::Input(ref name, _) => name.to_owned(),
+                _ => "".to_owned(),
             };
 
             let nodes = &mut self.nodes;
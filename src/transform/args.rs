@@ -0,0 +1,93 @@
+use crate::error::{TransformError, TransformResult};
+
+use rvs_parser::ast;
+
+/// Arguments of a type bound to its parameters
+///
+/// Arguments may be given by position, by name (`<name> = <expr>`), or both.  Positional
+/// arguments must come before named arguments.
+pub struct Args<'a> {
+    typ: &'static str,
+    params: &'static [&'static str],
+    values: Vec<Option<&'a ast::Node>>,
+}
+
+impl<'a> Args<'a> {
+    pub fn bind(
+        typ: &'static str,
+        params: &'static [&'static str],
+        args: &'a [Box<ast::Node>],
+    ) -> TransformResult<Args<'a>> {
+        let mut values: Vec<Option<&ast::Node>> = vec![None; params.len()];
+        let mut named = false;
+
+        for (position, arg) in args.iter().enumerate() {
+            let (index, value) = match **arg {
+                ast::Node::NamedArg(ref name, ref value) => {
+                    named = true;
+                    match params.iter().position(|param| param == name) {
+                        Some(index) => (index, &**value),
+                        None => {
+                            return Err(TransformError::new(format!(
+                                "{} has no parameter '{}'.  Expected one of {:?}",
+                                typ, name, params
+                            )));
+                        }
+                    }
+                }
+                _ => {
+                    if named {
+                        return Err(TransformError::new(format!(
+                            "Positional argument {} of {} follows a named argument",
+                            position + 1,
+                            typ
+                        )));
+                    }
+                    if position >= params.len() {
+                        return Err(TransformError::new(format!(
+                            "{} takes at most {} arguments but found {}",
+                            typ,
+                            params.len(),
+                            args.len()
+                        )));
+                    }
+                    (position, &**arg)
+                }
+            };
+
+            if values[index].is_some() {
+                return Err(TransformError::new(format!(
+                    "Parameter '{}' of {} is given more than once",
+                    params[index], typ
+                )));
+            }
+            values[index] = Some(value);
+        }
+
+        Ok(Args {
+            typ,
+            params,
+            values,
+        })
+    }
+
+    fn index(&self, name: &str) -> usize {
+        self.params
+            .iter()
+            .position(|param| *param == name)
+            .unwrap_or_else(|| panic!("{} has no parameter '{}'", self.typ, name))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&'a ast::Node> {
+        self.values[self.index(name)]
+    }
+
+    pub fn required(&self, name: &str) -> TransformResult<&'a ast::Node> {
+        self.get(name).ok_or_else(|| {
+            TransformError::new(format!(
+                "Missing required parameter '{}' of {}",
+                name, self.typ
+            ))
+        })
+    }
+}
@@ -3,10 +3,12 @@ use indexmap::IndexMap;
 #[derive(Debug)]
 pub struct Enum {
     pub items: IndexMap<String, u32>,
+    /// True if declared with `flags`
+    pub flags: bool,
 }
 
 impl Enum {
-    pub fn new(items: IndexMap<String, u32>) -> Enum {
-        Enum { items }
+    pub fn new(items: IndexMap<String, u32>, flags: bool) -> Enum {
+        Enum { items, flags }
     }
 }
@@ -1,4 +1,5 @@
 mod rand;
+mod args;
 mod enumeration;
 mod symbols;
 #[cfg_attr(feature = "cargo-clippy", allow(module_inception))]
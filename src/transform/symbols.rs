@@ -1,11 +1,14 @@
 use super::enumeration::Enum;
 
+use std::cell::Cell;
 use std::collections::HashMap;
+use std::rc::Rc;
 
 pub enum Symbol {
     Variable(usize),
     Enum(Enum),
     EnumMember(u32),
+    Placeholder(Rc<Cell<u32>>),
 }
 
 pub struct Symbols {
@@ -34,6 +37,21 @@ impl Symbols {
         self.symbols.insert(name.into(), Symbol::Variable(index));
     }
 
+    /// Inserts a placeholder that shadows any existing symbol of the same name
+    ///
+    /// Returns the shadowed symbol.  The shadowed symbol must be returned to `restore` when the
+    /// placeholder goes out of scope.
+    pub fn insert_placeholder(&mut self, name: &str, value: Rc<Cell<u32>>) -> Option<Symbol> {
+        self.symbols.insert(name.into(), Symbol::Placeholder(value))
+    }
+
+    pub fn restore(&mut self, name: &str, symbol: Option<Symbol>) {
+        match symbol {
+            Some(symbol) => self.symbols.insert(name.into(), symbol),
+            None => self.symbols.remove(name),
+        };
+    }
+
     pub fn contains(&self, name: &str) -> bool {
         self.symbols.contains_key(name)
     }
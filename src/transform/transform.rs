@@ -1,21 +1,40 @@
 use super::rand::{CrateRng, Seed};
 use super::enumeration::Enum;
 use super::symbols::{Symbol, Symbols};
+use super::args::Args;
 
 use crate::model::{Expr, Model, Variable, VariableRef};
-use crate::types::{Binary, Done, Next, Once, Pattern, Prev, Range, Sequence, Unary, Value,
-            WeightedWithReplacement, WeightedWithoutReplacement};
+use crate::types::{Alloc, Binary, Boundary, Burst, Call, Checkerboard, Combo, ConstraintField,
+            Cycle, Cyclic, Done, DynamicRange, Gray, Ids, Inject, Input, Intervals, Lfsr, Markov,
+            Near, Next, NoRepeat, NoRepeatSource, Once, Packed, Pattern, Placeholder, Prev, PrevN,
+            RandomBits, Range, RangeExcept, Repeat, SIGN_BIT, Sequence, SequenceEnd, Skip, Solver,
+            Take, Unary, Value, Walk, WalkingOnes, WalkingZeros, WeightedWithReplacement,
+            WeightedWithoutReplacement, Where, width_mask};
 use crate::error::{TransformError, TransformResult};
 
 use rvs_parser::ast;
 
 use indexmap::IndexMap;
 use std::rc::Rc;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::env;
 
 pub struct Transform {
     seed: Seed,
     symbols: Symbols,
+
+    /// Whether the variable being transformed is signed
+    signed: bool,
+
+    /// The width of the variable being transformed in bits
+    bits: u32,
+
+    /// Values of `arg()` lookups
+    args: HashMap<String, String>,
+
+    /// Warnings added to the model when the transform succeeds
+    warnings: Vec<String>,
 }
 
 impl Transform {
@@ -23,37 +42,115 @@ impl Transform {
         Transform {
             seed,
             symbols: Symbols::new(),
+            signed: false,
+            bits: 32,
+            args: HashMap::new(),
+            warnings: Vec::new(),
         }
     }
 
+    /// Sets the value returned by `arg("<key>", <default>)`
+    ///
+    /// The value must be a number literal.  E.g. `64`, `0x40`, or `4K`.
+    pub fn set_arg(&mut self, key: &str, value: &str) {
+        self.args.insert(key.into(), value.into());
+    }
+
     pub fn transform(
         &mut self,
         model: &mut Model,
         nodes: &[Box<ast::Node>],
     ) -> TransformResult<()> {
+        self.warnings.clear();
+
         for node in nodes {
             match **node {
                 ast::Node::Variable(ref name, ref expr) => {
-                    let variable = self.transform_variable(model, expr)?;
+                    let (width, expr) = match **expr {
+                        ast::Node::Width(width, ref expr) => (Some(width), expr),
+                        _ => (None, expr),
+                    };
+
+                    self.signed = width.is_some_and(|width| width.signed);
+                    self.bits = width.map_or(32, |width| width.bits.min(32));
+                    let variable = self.transform_variable(model, expr);
+                    self.signed = false;
+                    self.bits = 32;
+
+                    let variable = variable?;
+                    if let Some(width) = width {
+                        if width.bits == 0 || width.bits > 32 {
+                            return Err(TransformError::new(format!(
+                                "Expected a width in [1, 32] for '{}' but found {}",
+                                name, width.bits
+                            )));
+                        }
+
+                        variable.borrow_mut().set_width(width);
+
+                        if width.overflow == ast::Overflow::Error {
+                            let mut values = Vec::new();
+                            self.known_values(expr, &mut values);
+
+                            let overflow = values.into_iter().find(|&value| !variable.borrow().fits(value));
+                            if let Some(value) = overflow {
+                                let value = if width.signed {
+                                    i64::from(value as i32)
+                                } else {
+                                    i64::from(value)
+                                };
+
+                                return Err(TransformError::new(format!(
+                                    "Expected values that fit in {} bits for '{}' but found {}",
+                                    width.bits, name, value
+                                )));
+                            }
+                        }
+                    }
+
+                    let variable_index = model.add_variable(name, variable);
+                    self.symbols.insert_variable(name, variable_index);
+
+                    if let ast::Node::Packed(ref fields) = **expr {
+                        self.transform_field_views(model, name, variable_index, fields);
+                    }
+                }
+                ast::Node::Input(ref name, ref expr) => {
+                    let mut rng = self.seed.to_rng();
+                    let value = self.transform_expr(model, &mut rng, expr)?.next(&mut rng);
+                    let variable = Rc::new(RefCell::new(Box::new(Variable::new_input(Box::new(
+                        Input::new(value),
+                    )))));
+
                     let variable_index = model.add_variable(name, variable);
                     self.symbols.insert_variable(name, variable_index);
                 }
                 ast::Node::Enum(ref name, ref items) => {
-                    self.transform_enum(name, items)?;
+                    self.transform_enum(name, items, false)?;
+                }
+                ast::Node::Flags(ref name, ref items) => {
+                    self.transform_enum(name, items, true)?;
+                }
+                ast::Node::Constraint(ref name, ref members) => {
+                    self.transform_constraint(model, name, members)?;
                 }
                 _ => {
                     return Err(TransformError::new(format!(
-                        "expected Variable or Enum but found {:?}",
+                        "expected Variable, Input, Enum, Flags, or Constraint but found {:?}",
                         node
                     )));
                 }
             }
         }
 
+        for warning in self.warnings.drain(..) {
+            model.add_warning(warning);
+        }
+
         Ok(())
     }
 
-    fn transform_variable(&self, model: &Model, expr: &ast::Node) -> TransformResult<VariableRef> {
+    fn transform_variable(&mut self, model: &Model, expr: &ast::Node) -> TransformResult<VariableRef> {
         let mut rng = self.seed.to_rng();
         let expr = self.transform_expr(model, &mut rng, expr)?;
         let variable = Rc::new(RefCell::new(Box::new(Variable::new(expr, rng))));
@@ -61,7 +158,16 @@ impl Transform {
         Ok(variable)
     }
 
-    fn transform_enum(&mut self, name: &str, items: &[Box<ast::Node>]) -> TransformResult<()> {
+    /// Transforms an `enum` or `flags` declaration
+    ///
+    /// Implicit enum member values count up from the previous value.  Implicit flags member
+    /// values are the next power of two above the previous value.
+    fn transform_enum(
+        &mut self,
+        name: &str,
+        items: &[Box<ast::Node>],
+        flags: bool,
+    ) -> TransformResult<()> {
         if self.symbols.contains(name) {
             return Err(TransformError::new(format!(
                 "Symbol '{}' already exists",
@@ -70,30 +176,55 @@ impl Transform {
         }
 
         let mut enum_members_map = IndexMap::new();
-        let mut next_implicit_value = 0;
+        let mut next_implicit_value: i64 = if flags { 1 } else { 0 };
 
         // FIXME change to drain()?
         for item in items {
             if let ast::Node::EnumMember(ref member_name, ref value) = **item {
                 let full_name = format!("{}::{}", name, member_name);
-                if let Some(ref value) = *value {
-                    if let ast::Node::Number(value) = **value {
+                let value: i64 = if let Some(ref value) = *value {
+                    match **value {
                         // FIXME Check for existence
-                        enum_members_map.insert(member_name.to_owned(), value);
-                        self.symbols.insert_enum_member(full_name, value);
-                        next_implicit_value = value + 1;
-                    } else {
-                        return Err(TransformError::new(format!(
-                            "Expected Number but found {:?}",
-                            **value
-                        )));
+                        ast::Node::Number(value) => i64::from(value),
+                        ast::Node::UnaryOperation(ast::UnaryOpcode::Neg, ref value) if !flags => {
+                            match **value {
+                                ast::Node::Number(value) => -i64::from(value),
+                                _ => {
+                                    return Err(TransformError::new(format!(
+                                        "Expected Number but found {:?}",
+                                        **value
+                                    )));
+                                }
+                            }
+                        }
+                        _ => {
+                            return Err(TransformError::new(format!(
+                                "Expected Number but found {:?}",
+                                **value
+                            )));
+                        }
                     }
                 } else {
-                    enum_members_map.insert(member_name.to_owned(), next_implicit_value);
-                    self.symbols
-                        .insert_enum_member(full_name, next_implicit_value);
-                    next_implicit_value += 1;
+                    next_implicit_value
+                };
+
+                if value < i64::from(i32::MIN) || value > i64::from(u32::MAX) {
+                    return Err(TransformError::new(format!(
+                        "The value of '{}' does not fit in 32 bits",
+                        full_name
+                    )));
                 }
+
+                // Negative values are stored as two's complement
+                let member_value = value as u32;
+
+                enum_members_map.insert(member_name.to_owned(), member_value);
+                self.symbols.insert_enum_member(full_name, member_value);
+                next_implicit_value = if flags {
+                    1 << (32 - member_value.leading_zeros())
+                } else {
+                    value + 1
+                };
             } else {
                 return Err(TransformError::new(format!(
                     "Expected EnumMember but found {:?}",
@@ -101,13 +232,249 @@ impl Transform {
                 )));
             }
         }
-        self.symbols.insert_enum(name, Enum::new(enum_members_map));
+        self.symbols.insert_enum(name, Enum::new(enum_members_map, flags));
+
+        Ok(())
+    }
+
+    /// Transforms a `constraint` block into one variable per field
+    ///
+    /// The variables are named `<constraint>::<field>` and share a single `Solver`.  Field names
+    /// are only visible as-is inside the constraint expressions of the block.
+    ///
+    /// Comparisons of a field against a constant narrow the domain of the field before it is
+    /// handed to the `Solver`.  This keeps large domains tractable.
+    fn transform_constraint(
+        &mut self,
+        model: &mut Model,
+        name: &str,
+        members: &[Box<ast::Node>],
+    ) -> TransformResult<()> {
+        let mut rng = self.seed.to_rng();
+        let mut fields: Vec<String> = Vec::new();
+        let mut domains: Vec<Intervals> = Vec::new();
+        let mut relations: Vec<&ast::Node> = Vec::new();
+
+        for member in members {
+            match **member {
+                ast::Node::Variable(ref field, ref domain) => {
+                    if fields.contains(field) {
+                        return Err(TransformError::new(format!(
+                            "Field '{}' defined more than once in constraint '{}'",
+                            field, name
+                        )));
+                    }
+
+                    let domain = self.transform_domain(model, &mut rng, domain)?;
+                    if domain.is_empty() {
+                        return Err(TransformError::new(format!(
+                            "Field '{}' of constraint '{}' has an empty domain",
+                            field, name
+                        )));
+                    }
+
+                    fields.push(field.clone());
+                    domains.push(domain);
+                }
+                _ => relations.push(member),
+            }
+        }
+
+        if fields.is_empty() {
+            return Err(TransformError::new(format!(
+                "Expected at least one field in constraint '{}'",
+                name
+            )));
+        }
+
+        let values: Vec<Rc<Cell<u32>>> = fields.iter().map(|_| Rc::new(Cell::new(0))).collect();
+        let shadowed: Vec<_> = fields
+            .iter()
+            .zip(values.iter())
+            .map(|(field, value)| self.symbols.insert_placeholder(field, Rc::clone(value)))
+            .collect();
+
+        for relation in relations.iter() {
+            for (field, domain) in fields.iter().zip(domains.iter_mut()) {
+                self.narrow_domain(domain, relation, field);
+            }
+        }
+
+        let host_driven = relations.iter().any(|relation| self.is_host_driven(model, relation));
+
+        let mut constraints = Vec::new();
+        let mut result = Ok(());
+        for relation in relations {
+            match self.transform_expr(model, &mut rng, relation) {
+                Ok(expr) => {
+                    let referenced: Vec<usize> = fields
+                        .iter()
+                        .enumerate()
+                        .filter(|&(_, field)| references(relation, field))
+                        .map(|(index, _)| index)
+                        .collect();
+                    constraints.push((referenced, expr));
+                }
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+
+        for (field, symbol) in fields.iter().zip(shadowed).rev() {
+            self.symbols.restore(field, symbol);
+        }
+        result?;
+
+        let mut solver = Solver::new(
+            name,
+            fields.clone(),
+            domains,
+            values,
+            constraints,
+            self.seed.to_rng(),
+        );
+
+        if let Some(field) = solver.empty_field() {
+            return Err(TransformError::new(format!(
+                "No value of field '{}' satisfies the constraints of '{}'",
+                field, name
+            )));
+        }
+        if !host_driven && !solver.solvable() {
+            return Err(TransformError::new(format!(
+                "Expected a solution for the constraints of '{}' but found none within {} steps",
+                name,
+                Solver::STEP_LIMIT
+            )));
+        }
+
+        let solver = Rc::new(RefCell::new(solver));
+        for (index, field) in fields.iter().enumerate() {
+            let full_name = format!("{}::{}", name, field);
+            let expr = Box::new(ConstraintField::new(Rc::clone(&solver), index));
+            let variable = Rc::new(RefCell::new(Box::new(Variable::new(expr, self.seed.to_rng()))));
+            let variable_index = model.add_variable(&full_name, variable);
+            self.symbols.insert_variable(&full_name, variable_index);
+        }
 
         Ok(())
     }
 
+    /// Evaluates the domain of a constraint field
+    ///
+    /// A domain is a Number, an EnumMember, a Range, or a set of these with an optional `except`
+    /// clause.  Weights are ignored.
+    fn transform_domain(
+        &mut self,
+        model: &Model,
+        rng: &mut CrateRng,
+        node: &ast::Node,
+    ) -> TransformResult<Intervals> {
+        if let Some(value) = self.constant(node) {
+            return Ok(Intervals::new(value, value));
+        }
+
+        match *node {
+            ast::Node::Type(ast::Type::Range, ref args) => {
+                let l = self.evaluate(model, rng, &args[0])?;
+                let r = self.evaluate(model, rng, &args[1])?;
+
+                Ok(Intervals::new(l, r))
+            }
+            ast::Node::Weighted(_, ref entries) => {
+                let mut domain = Intervals::default();
+                for entry in entries {
+                    match **entry {
+                        ast::Node::WeightedSample(_, ref node) => {
+                            for &(l, r) in self.transform_domain(model, rng, node)?.iter() {
+                                domain.insert(l, r);
+                            }
+                        }
+                        _ => {
+                            return Err(TransformError::new(format!(
+                                "Expected WeightedSample but found {:?}",
+                                **entry
+                            )));
+                        }
+                    }
+                }
+
+                Ok(domain)
+            }
+            ast::Node::Except(ref node, ref excluded) => {
+                let mut domain = self.transform_domain(model, rng, node)?;
+                for (l, r) in self.transform_excluded(model, rng, excluded)? {
+                    domain.remove(l, r);
+                }
+
+                Ok(domain)
+            }
+            _ => Err(TransformError::new(format!(
+                "Expected a Number, EnumMember, Range, or set as a constraint field domain but found {:?}",
+                *node
+            ))),
+        }
+    }
+
+    /// Narrows the domain of a constraint field by comparisons of the field against constants
+    ///
+    /// Only comparisons that must hold for the whole constraint are used.  I.e. the constraint
+    /// itself or the operands of `&&`.
+    fn narrow_domain(&self, domain: &mut Intervals, node: &ast::Node, field: &str) {
+        let is_field = |node: &ast::Node| match *node {
+            ast::Node::RIdentifier(ref name, ast::VariableMethod::Next) => name == field,
+            _ => false,
+        };
+
+        if let ast::Node::BinaryOperation(ref a, ref op, ref b) = *node {
+            let comparison = if is_field(a) {
+                self.constant(b).map(|value| (op.clone(), value))
+            } else if is_field(b) {
+                self.constant(a).and_then(|value| {
+                    let op = match *op {
+                        ast::BinaryOpcode::Lt => ast::BinaryOpcode::Gt,
+                        ast::BinaryOpcode::Le => ast::BinaryOpcode::Ge,
+                        ast::BinaryOpcode::Gt => ast::BinaryOpcode::Lt,
+                        ast::BinaryOpcode::Ge => ast::BinaryOpcode::Le,
+                        ast::BinaryOpcode::Eq | ast::BinaryOpcode::Ne => op.clone(),
+                        _ => return None,
+                    };
+                    Some((op, value))
+                })
+            } else {
+                None
+            };
+
+            match comparison {
+                Some((ast::BinaryOpcode::Lt, value)) => domain.remove(value, u32::MAX),
+                Some((ast::BinaryOpcode::Le, value)) if value < u32::MAX => {
+                    domain.remove(value + 1, u32::MAX)
+                }
+                Some((ast::BinaryOpcode::Gt, value)) => domain.remove(0, value),
+                Some((ast::BinaryOpcode::Ge, value)) if value > 0 => domain.remove(0, value - 1),
+                Some((ast::BinaryOpcode::Eq, value)) => {
+                    if value > 0 {
+                        domain.remove(0, value - 1);
+                    }
+                    if value < u32::MAX {
+                        domain.remove(value + 1, u32::MAX);
+                    }
+                }
+                Some((ast::BinaryOpcode::Ne, value)) => domain.remove(value, value),
+                _ => {
+                    if let ast::BinaryOpcode::LogicalAnd = *op {
+                        self.narrow_domain(domain, a, field);
+                        self.narrow_domain(domain, b, field);
+                    }
+                }
+            }
+        }
+    }
+
     fn transform_expr(
-        &self,
+        &mut self,
         model: &Model,
         rng: &mut CrateRng,
         node: &ast::Node,
@@ -115,17 +482,29 @@ impl Transform {
         match *node {
             ast::Node::Type(ref typ, ref args) => self.transform_type(model, rng, typ, args),
             ast::Node::Weighted(ref replacement, ref args) =>
-                self.transform_weighted(model, rng, replacement, args),
+                self.transform_weighted(model, rng, replacement, args, &[]),
+            ast::Node::Except(ref node, ref excluded) =>
+                self.transform_except(model, rng, node, excluded),
+            ast::Node::Markov(ref args, ref transitions) =>
+                self.transform_markov(model, rng, args, transitions),
+            ast::Node::Packed(ref fields) => self.transform_packed(model, rng, fields),
             ast::Node::Number(x) => Ok(Box::new(Value::new(x))),
             ast::Node::UnaryOperation(ref op, ref a) => Ok(Box::new(Unary::new(
                 op.clone(),
                 self.transform_expr(model, rng, a)?,
             ))),
-            ast::Node::BinaryOperation(ref bx, ref op, ref by) => Ok(Box::new(Binary::new(
-                self.transform_expr(model, rng, bx)?,
-                op.clone(),
-                self.transform_expr(model, rng, by)?,
-            ))),
+            ast::Node::BinaryOperation(ref bx, ref op, ref by) => {
+                Ok(Box::new(self.transform_binary(model, rng, bx, op, by)?))
+            }
+            ast::Node::DonePolicy(ref expr, policy, reset) => match **expr {
+                ast::Node::BinaryOperation(ref bx, ref op, ref by) => Ok(Box::new(
+                    self.transform_binary(model, rng, bx, op, by)?.with_done(policy, reset),
+                )),
+                _ => Err(TransformError::new(format!(
+                    "Expected BinaryOperation before .done() but found {:?}",
+                    **expr
+                ))),
+            },
             ast::Node::RIdentifier(ref name, ref method) => {
                 match self.symbols.get(name) {
                     Some(symbol) => {
@@ -142,6 +521,9 @@ impl Transform {
                                     name
                                 )))
                             }
+                            Symbol::Placeholder(ref value) => {
+                                Ok(Box::new(Placeholder::new(name, Rc::clone(value))))
+                            }
                         }
                     }
                     None => {
@@ -152,6 +534,54 @@ impl Transform {
                     }
                 }
             }
+            ast::Node::Lookup(source, ref key, ref default) => {
+                let value = match source {
+                    ast::Lookup::Env => match env::var(key) {
+                        Ok(value) => Some(value),
+                        Err(env::VarError::NotPresent) => None,
+                        Err(env::VarError::NotUnicode(value)) => {
+                            return Err(TransformError::new(format!(
+                                "Expected a number for env(\"{}\") but found {:?}",
+                                key, value
+                            )));
+                        }
+                    },
+                    ast::Lookup::Arg => self.args.get(key).cloned(),
+                };
+
+                match value {
+                    Some(value) => match rvs_parser::parse_number(&value) {
+                        Some(value) => Ok(Box::new(Value::new(value))),
+                        None => Err(TransformError::new(format!(
+                            "Expected a number for {}(\"{}\") but found '{}'",
+                            source, key, value
+                        ))),
+                    },
+                    None => self.transform_expr(model, rng, default),
+                }
+            }
+            ast::Node::Call(ref name, ref args) => {
+                let function = match model.get_function(name) {
+                    Some(function) => function.clone(),
+                    None => {
+                        return Err(TransformError::new(format!(
+                            "Could not find function '{}'",
+                            name
+                        )));
+                    }
+                };
+                if function.arity() != args.len() {
+                    return Err(TransformError::new(format!(
+                        "Expected {} arguments for function '{}' but found {}",
+                        function.arity(),
+                        name,
+                        args.len()
+                    )));
+                }
+
+                let args = self.transform_args(model, rng, args)?;
+                Ok(Box::new(Call::new(name, function, args)))
+            }
             _ => Err(TransformError::new(format!(
                 "Expected (Type|Number|UnaryOperation|BinaryOperation|Identifier) but found {:?}",
                 *node
@@ -159,6 +589,24 @@ impl Transform {
         }
     }
 
+    fn transform_binary(
+        &mut self,
+        model: &Model,
+        rng: &mut CrateRng,
+        bx: &ast::Node,
+        op: &ast::BinaryOpcode,
+        by: &ast::Node,
+    ) -> TransformResult<Binary> {
+        let l = self.transform_expr(model, rng, bx)?;
+        let r = self.transform_expr(model, rng, by)?;
+
+        if self.signed {
+            Ok(Binary::new_signed(l, op.clone(), r))
+        } else {
+            Ok(Binary::new(l, op.clone(), r))
+        }
+    }
+
     fn transform_r_variable(
         &self,
         model: &Model,
@@ -174,7 +622,32 @@ impl Transform {
                 ast::VariableMethod::Prev => {
                     Ok(Box::new(Prev::new(variable_name, Rc::downgrade(variable))))
                 }
-                ast::VariableMethod::Copy => Ok(variable.borrow().clone_expr()),
+                ast::VariableMethod::PrevN(n) => {
+                    if n == 0 {
+                        return Err(TransformError::new(format!(
+                            "Expected a history depth of at least 1 for '{}' but found 0",
+                            variable_name
+                        )));
+                    }
+
+                    let mut borrowed = variable.borrow_mut();
+                    if n as usize > borrowed.history_depth() {
+                        borrowed.set_history_depth(n as usize);
+                    }
+
+                    Ok(Box::new(PrevN::new(variable_name, Rc::downgrade(variable), n)))
+                }
+                ast::VariableMethod::Copy => {
+                    let borrowed = variable.borrow();
+                    if borrowed.is_input() {
+                        return Err(TransformError::new(format!(
+                            "Cannot copy the input '{}'",
+                            variable_name
+                        )));
+                    }
+
+                    Ok(borrowed.clone_expr())
+                }
             },
             None => Err(TransformError::new(format!(
                 "Could not find variable '{}'",
@@ -184,7 +657,7 @@ impl Transform {
     }
 
     fn transform_args(
-        &self,
+        &mut self,
         model: &Model,
         rng: &mut CrateRng,
         args: &[Box<ast::Node>],
@@ -198,35 +671,55 @@ impl Transform {
     }
 
     fn transform_weighted(
-        &self,
+        &mut self,
         model: &Model,
         rng: &mut CrateRng,
         replacement: &ast::Replacement,
         args: &[Box<ast::Node>],
+        excluded: &[(u32, u32)],
     ) -> TransformResult<Box<dyn Expr>> {
         let mut weights: Vec<u32> = Vec::new();
         let mut children: Vec<Box<dyn Expr>> = Vec::new();
         for arg in args {
             match **arg {
                 ast::Node::Type(ast::Type::Expand, ref args) => {
+                    if self.is_host_driven(model, &args[0]) {
+                        return Err(TransformError::new(format!(
+                            "Expected a value known at transform time but found the host-driven {:?}",
+                            args[0]
+                        )));
+                    }
+
                     let mut expr = self.transform_expr(model, rng, &args[0])?;
+                    let mut values: Vec<u32> = Vec::new();
 
                     if args.len() == 1 {
                         while !expr.done() {
-                            weights.push(1);
-                            children.push(Box::new(Value::new(expr.next(rng))));
+                            values.push(expr.next(rng));
                         }
                     } else {
-                        let mut count = self.transform_expr(model, rng, &args[1])?;
-                        for _ in 0..count.next(rng) {
+                        for _ in 0..self.evaluate(model, rng, &args[1])? {
+                            values.push(expr.next(rng));
+                        }
+                    }
+
+                    for value in values {
+                        if !is_excluded(value, excluded, self.signed) {
                             weights.push(1);
-                            children.push(Box::new(Value::new(expr.next(rng))));
+                            children.push(Box::new(Value::new(value)));
                         }
                     }
                 }
                 ast::Node::WeightedSample(ref weight, ref node) => {
-                    weights.push(*weight);
-                    children.push(self.transform_expr(model, rng, node)?);
+                    if excluded.is_empty() {
+                        weights.push(*weight);
+                        children.push(self.transform_expr(model, rng, node)?);
+                    } else if let Some(child) =
+                        self.transform_except_member(model, rng, node, excluded)?
+                    {
+                        weights.push(*weight);
+                        children.push(child);
+                    }
                 }
                 _ => {
                     return Err(TransformError::new(format!(
@@ -237,57 +730,1063 @@ impl Transform {
             }
         }
 
+        if children.is_empty() {
+            return Err(TransformError::new(format!(
+                "Expected at least one member but all members were excluded by {:?}",
+                excluded
+            )));
+        }
+
+        if weights.iter().all(|weight| *weight == 0) {
+            return Err(TransformError::new(format!(
+                "Expected at least one non-zero weight but found weights {:?}",
+                weights
+            )));
+        }
+
         match *replacement {
             ast::Replacement::With => Ok(Box::new(WeightedWithReplacement::new(weights, children))),
             ast::Replacement::Without => Ok(Box::new(WeightedWithoutReplacement::new(
                 weights,
                 children,
-                rng,
             ))),
         }
     }
 
-    fn transform_type(
-        &self,
+    fn transform_range(
+        &mut self,
         model: &Model,
         rng: &mut CrateRng,
-        typ: &ast::Type,
         args: &[Box<ast::Node>],
+        excluded: &[(u32, u32)],
     ) -> TransformResult<Box<dyn Expr>> {
-        match *typ {
-            ast::Type::Pattern => Ok(Box::new(Pattern::new(self.transform_args(
-                model,
-                rng,
-                args,
-            )?))),
-            ast::Type::Sequence => {
-                let args = self.transform_args(model, rng, args)?;
+        if excluded.is_empty() && args.iter().any(|arg| self.is_host_driven(model, arg)) {
+            let l = self.transform_expr(model, rng, &args[0])?;
+            let r = self.transform_expr(model, rng, &args[1])?;
 
-                Ok(Box::new(Sequence::new(args, rng)))
-            }
-            ast::Type::Range => {
-                let l = self.transform_expr(model, rng, &args[0])?.next(rng);
-                let r = self.transform_expr(model, rng, &args[1])?.next(rng);
+            return Ok(Box::new(DynamicRange::new(l, r, self.signed)));
+        }
 
-                // Elide the range for case when limits are equal
-                //
-                // The underlying rand::distributions::Range treats this case as an error.  We
-                // don't want an error so catch and handle gracefully.
-                if l == r {
-                    Ok(Box::new(Value::new(1)))
-                } else {
-                    Ok(Box::new(Range::new(l, r)))
-                }
+        let l = self.evaluate(model, rng, &args[0])?;
+        let r = self.evaluate(model, rng, &args[1])?;
+
+        if !excluded.is_empty() {
+            if RangeExcept::allowed_values(l, r, excluded, self.signed).is_empty() {
+                return Err(TransformError::new(format!(
+                    "The range [0x{:x}, 0x{:x}] has no values left after excluding {:?}",
+                    l, r, excluded
+                )));
             }
-            ast::Type::Expand => Err(TransformError::new("Expand() must be inside {}".to_owned())),
-            ast::Type::Done => {
-                let expr = self.transform_expr(model, rng, &*args[0])?;
-                Ok(Box::new(Done::new(expr)))
+
+            return Ok(Box::new(self.range_except(l, r, excluded)));
+        }
+
+        // Elide the range for case when limits are equal
+        //
+        // The underlying rand::distributions::Range treats this case as an error.  We
+        // don't want an error so catch and handle gracefully.
+        if l == r && self.signed {
+            Ok(Box::new(Value::new_signed(l)))
+        } else if l == r {
+            Ok(Box::new(Value::new(l)))
+        } else if self.signed {
+            Ok(Box::new(Range::new_signed(l, r)))
+        } else {
+            Ok(Box::new(Range::new(l, r)))
+        }
+    }
+
+    /// Returns true if `node` calls a host function or references an input
+    ///
+    /// The values of such nodes change between draws and must not be evaluated at transform time.
+    fn is_host_driven(&self, model: &Model, node: &ast::Node) -> bool {
+        any_node(node, &|node| match *node {
+            ast::Node::Call(_, _) => true,
+            ast::Node::RIdentifier(ref name, _) => match self.symbols.get(name) {
+                Some(Symbol::Variable(index)) => model
+                    .get_variable_by_index(*index)
+                    .is_some_and(|variable| variable.borrow().is_input()),
+                _ => false,
+            },
+            _ => false,
+        })
+    }
+
+    /// Transforms `node` and evaluates it to a single value at transform time
+    fn evaluate(
+        &mut self,
+        model: &Model,
+        rng: &mut CrateRng,
+        node: &ast::Node,
+    ) -> TransformResult<u32> {
+        if self.is_host_driven(model, node) {
+            return Err(TransformError::new(format!(
+                "Expected a value known at transform time but found the host-driven {:?}",
+                node
+            )));
+        }
+
+        Ok(self.transform_expr(model, rng, node)?.next(rng))
+    }
+
+    /// Returns an error if the variable being transformed is signed
+    ///
+    /// Used by types that only produce unsigned values.  E.g. identifiers and addresses.
+    fn check_unsigned(&self, typ: &str) -> TransformResult<()> {
+        if self.signed {
+            return Err(TransformError::new(format!(
+                "Expected {} in an unsigned variable but found a signed variable",
+                typ
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Returns a RangeExcept with the sign of the variable being transformed
+    fn range_except(&self, l: u32, r: u32, excluded: &[(u32, u32)]) -> RangeExcept {
+        if self.signed {
+            RangeExcept::new_signed(l, r, excluded.to_vec())
+        } else {
+            RangeExcept::new(l, r, excluded.to_vec())
+        }
+    }
+
+    fn transform_except(
+        &mut self,
+        model: &Model,
+        rng: &mut CrateRng,
+        node: &ast::Node,
+        excluded: &[Box<ast::Node>],
+    ) -> TransformResult<Box<dyn Expr>> {
+        let excluded = self.transform_excluded(model, rng, excluded)?;
+
+        match *node {
+            ast::Node::Type(ast::Type::Range, ref args) => {
+                self.transform_range(model, rng, args, &excluded)
             }
-            ast::Type::Once => {
-                let expr = self.transform_expr(model, rng, &*args[0])?;
-                Ok(Box::new(Once::new(expr)))
+            ast::Node::Weighted(ref replacement, ref args) => {
+                self.transform_weighted(model, rng, replacement, args, &excluded)
             }
+            _ => Err(TransformError::new(format!(
+                "Expected Range or Weighted before except but found {:?}",
+                *node
+            ))),
+        }
+    }
+
+    /// Evaluates the members of an `except` clause
+    ///
+    /// Ranges are evaluated to their limits.  All other expressions are evaluated to a single
+    /// value.
+    fn transform_excluded(
+        &mut self,
+        model: &Model,
+        rng: &mut CrateRng,
+        excluded: &[Box<ast::Node>],
+    ) -> TransformResult<Vec<(u32, u32)>> {
+        let mut intervals: Vec<(u32, u32)> = Vec::new();
+        for node in excluded {
+            match **node {
+                ast::Node::Type(ast::Type::Range, ref args) => {
+                    let l = self.evaluate(model, rng, &args[0])?;
+                    let r = self.evaluate(model, rng, &args[1])?;
+                    let offset = if self.signed { SIGN_BIT } else { 0 };
+                    intervals.push(if r ^ offset > l ^ offset { (l, r) } else { (r, l) });
+                }
+                _ => {
+                    let value = self.evaluate(model, rng, node)?;
+                    intervals.push((value, value));
+                }
+            }
+        }
+
+        Ok(intervals)
+    }
+
+    /// Applies an `except` clause to a single member of a weighted set
+    ///
+    /// Returns `None` if the member is excluded entirely.
+    fn transform_except_member(
+        &mut self,
+        model: &Model,
+        rng: &mut CrateRng,
+        node: &ast::Node,
+        excluded: &[(u32, u32)],
+    ) -> TransformResult<Option<Box<dyn Expr>>> {
+        if let Some(value) = self.constant(node) {
+            if is_excluded(value, excluded, self.signed) {
+                return Ok(None);
+            } else {
+                return Ok(Some(Box::new(Value::new(value))));
+            }
+        }
+
+        match *node {
+            ast::Node::Type(ast::Type::Range, ref args) => {
+                let l = self.evaluate(model, rng, &args[0])?;
+                let r = self.evaluate(model, rng, &args[1])?;
+
+                if RangeExcept::allowed_values(l, r, excluded, self.signed).is_empty() {
+                    Ok(None)
+                } else {
+                    Ok(Some(Box::new(self.range_except(l, r, excluded))))
+                }
+            }
+            ast::Node::Except(ref node, ref more) => {
+                let mut excluded = excluded.to_vec();
+                excluded.extend(self.transform_excluded(model, rng, more)?);
+
+                self.transform_except_member(model, rng, node, &excluded)
+            }
+            _ => Err(TransformError::new(format!(
+                "Expected a Number, EnumMember, or Range member with except but found {:?}",
+                *node
+            ))),
+        }
+    }
+
+    fn transform_markov(
+        &mut self,
+        model: &Model,
+        rng: &mut CrateRng,
+        args: &[Box<ast::Node>],
+        transitions: &[(Box<ast::Node>, Box<ast::Node>)],
+    ) -> TransformResult<Box<dyn Expr>> {
+        let mut transition_exprs = IndexMap::new();
+        for (state, transition) in transitions {
+            let state = match self.constant(state) {
+                Some(state) => state,
+                None => {
+                    return Err(TransformError::new(format!(
+                        "Expected a Number or EnumMember as a Markov state but found {:?}",
+                        **state
+                    )));
+                }
+            };
+
+            if transition_exprs.contains_key(&state) {
+                return Err(TransformError::new(format!(
+                    "Markov state 0x{:x} has more than one transition",
+                    state
+                )));
+            }
+
+            let transition = self.transform_expr(model, rng, transition)?;
+            transition_exprs.insert(state, transition);
+        }
+
+        let mut states = Vec::new();
+        if let Some(init) = args.first() {
+            self.constant_states(init, &mut states);
+        }
+        for (_, transition) in transitions {
+            self.constant_states(transition, &mut states);
+        }
+        // A range with more states than there are transitions is missing one among its first
+        // transitions + 1 states
+        let limit = transition_exprs.len() as u64 + 1;
+        for (l, r) in states {
+            let missing = (0..=u64::from(r.wrapping_sub(l)).min(limit))
+                .map(|offset| l.wrapping_add(offset as u32))
+                .find(|state| !transition_exprs.contains_key(state));
+
+            if let Some(state) = missing {
+                return Err(TransformError::new(format!(
+                    "Markov state 0x{:x} has no transition",
+                    state
+                )));
+            }
+        }
+
+        let init: Box<dyn Expr> = match args.first() {
+            Some(init) => self.transform_expr(model, rng, init)?,
+            None => Box::new(Value::new(*transition_exprs.keys().next().unwrap())),
+        };
+
+        let count = match args.get(1) {
+            Some(count) => Some(self.transform_expr(model, rng, count)?),
+            None => None,
+        };
+
+        Ok(Box::new(Markov::new(init, count, transition_exprs, rng)))
+    }
+
+    /// Transforms a Sequence
+    ///
+    /// A single positional argument is `last`.  Otherwise positional arguments are `first`,
+    /// `last`, and `increment`.
+    fn transform_sequence(
+        &mut self,
+        model: &Model,
+        rng: &mut CrateRng,
+        args: &[Box<ast::Node>],
+    ) -> TransformResult<Box<dyn Expr>> {
+        let wrap = args
+            .iter()
+            .any(|arg| matches!(**arg, ast::Node::NamedArg(ref name, _) if name == "wrap"));
+
+        let args = match args {
+            [arg] if !matches!(**arg, ast::Node::NamedArg(..)) => {
+                Args::bind("Sequence", &["last", "first", "increment", "count", "wrap"], args)?
+            }
+            _ if wrap => {
+                Args::bind("Sequence", &["first", "count", "increment", "wrap", "last"], args)?
+            }
+            _ => Args::bind("Sequence", &["first", "last", "increment", "count", "wrap"], args)?,
+        };
+
+        let first: Box<dyn Expr> = match args.get("first") {
+            Some(first) => self.transform_expr(model, rng, first)?,
+            None => Box::new(Value::new(0)),
+        };
+
+        let increment: Box<dyn Expr> = match args.get("increment") {
+            Some(increment) => self.transform_expr(model, rng, increment)?,
+            None => Box::new(Value::new(1)),
+        };
+
+        let end = match (args.get("last"), args.get("count"), args.get("wrap")) {
+            (Some(last), None, None) => SequenceEnd::Last(self.transform_expr(model, rng, last)?),
+            (None, Some(count), wrap) => {
+                let wrap = match wrap {
+                    Some(wrap) => Some(self.transform_expr(model, rng, wrap)?),
+                    None => None,
+                };
+
+                SequenceEnd::Count(self.transform_expr(model, rng, count)?, wrap)
+            }
+            (Some(_), Some(_), _) => {
+                return Err(TransformError::new(
+                    "Sequence takes either 'last' or 'count' but found both".to_owned(),
+                ));
+            }
+            (Some(_), None, Some(_)) => {
+                return Err(TransformError::new(
+                    "Sequence requires 'count' when 'wrap' is given".to_owned(),
+                ));
+            }
+            (None, None, _) => {
+                return Err(TransformError::new(
+                    "Sequence requires either 'last' or 'count'".to_owned(),
+                ));
+            }
+        };
+
+        let host_driven = ["first", "last", "increment", "count", "wrap"]
+            .iter()
+            .filter_map(|name| args.get(name))
+            .any(|arg| self.is_host_driven(model, arg));
+
+        if host_driven {
+            Ok(Box::new(Sequence::deferred(first, end, increment, self.signed)))
+        } else if self.signed {
+            Ok(Box::new(Sequence::new_signed(first, end, increment, rng)))
+        } else {
+            Ok(Box::new(Sequence::new(first, end, increment, rng)))
+        }
+    }
+
+    /// Returns the member values of a `flags` type or of a set of constants
+    fn transform_flags(&self, node: &ast::Node) -> TransformResult<Vec<u32>> {
+        match *node {
+            ast::Node::RIdentifier(ref name, ast::VariableMethod::Next) => {
+                match self.symbols.get(name) {
+                    Some(Symbol::Enum(enumeration)) if enumeration.flags => {
+                        Ok(enumeration.items.values().cloned().collect())
+                    }
+                    _ => Err(TransformError::new(format!(
+                        "Expected a flags type but found '{}'",
+                        name
+                    ))),
+                }
+            }
+            ast::Node::Weighted(ast::Replacement::Without, ref entries) => entries
+                .iter()
+                .map(|entry| match **entry {
+                    ast::Node::WeightedSample(_, ref node) => self.constant(node),
+                    _ => None,
+                })
+                .collect::<Option<Vec<u32>>>()
+                .ok_or_else(|| {
+                    TransformError::new(format!(
+                        "Expected a set of Numbers or EnumMembers but found {:?}",
+                        *node
+                    ))
+                }),
+            _ => Err(TransformError::new(format!(
+                "Expected a flags type or a set of flags but found {:?}",
+                *node
+            ))),
+        }
+    }
+
+    /// Evaluates the `width` argument of a bit pattern type
+    fn transform_width(
+        &mut self,
+        model: &Model,
+        rng: &mut CrateRng,
+        args: &Args,
+    ) -> TransformResult<u32> {
+        let width = self.evaluate(model, rng, args.required("width")?)?;
+
+        if width == 0 || width > 32 {
+            return Err(TransformError::new(format!(
+                "Expected a width in [1, 32] but found {}",
+                width
+            )));
+        }
+
+        Ok(width)
+    }
+
+    /// Selects exact sampling for ranges and `r{}` sets of constants
+    ///
+    /// Exactly sampled domains must have more than `window` values.
+    fn transform_no_repeat_source(
+        &mut self,
+        model: &Model,
+        rng: &mut CrateRng,
+        node: &ast::Node,
+        window: u32,
+    ) -> TransformResult<NoRepeatSource> {
+        match *node {
+            ast::Node::Type(ast::Type::Range, ref args)
+                if !self.signed && !args.iter().any(|arg| self.is_host_driven(model, arg)) =>
+            {
+                let l = self.evaluate(model, rng, &args[0])?;
+                let r = self.evaluate(model, rng, &args[1])?;
+                let (l, r) = if r > l { (l, r) } else { (r, l) };
+
+                if u64::from(r - l) < u64::from(window) {
+                    return Err(TransformError::new(format!(
+                        "Expected more than {} values for NoRepeat but the range [0x{:x}, 0x{:x}] has {}",
+                        window,
+                        l,
+                        r,
+                        u64::from(r - l) + 1
+                    )));
+                }
+
+                return Ok(NoRepeatSource::Range(l, r));
+            }
+            ast::Node::Weighted(ast::Replacement::With, ref samples) => {
+                let members: Option<Vec<(u32, u32)>> = samples
+                    .iter()
+                    .map(|sample| match **sample {
+                        ast::Node::WeightedSample(weight, ref node) => {
+                            self.constant(node).map(|value| (weight, value))
+                        }
+                        _ => None,
+                    })
+                    .collect();
+
+                if let Some(members) = members {
+                    let mut values: Vec<u32> = members
+                        .iter()
+                        .filter(|(weight, _)| *weight != 0)
+                        .map(|(_, value)| *value)
+                        .collect();
+                    values.sort();
+                    values.dedup();
+
+                    if values.len() <= window as usize {
+                        return Err(TransformError::new(format!(
+                            "Expected more than {} values for NoRepeat but found {}",
+                            window,
+                            values.len()
+                        )));
+                    }
+
+                    return Ok(NoRepeatSource::Weighted(members));
+                }
+            }
+            _ => {}
+        }
+
+        Ok(NoRepeatSource::Expr(self.transform_expr(model, rng, node)?))
+    }
+
+    /// Transforms the `expr` and `count` arguments of a period type E.g. `Repeat`
+    fn transform_period(
+        &mut self,
+        model: &Model,
+        rng: &mut CrateRng,
+        typ: &'static str,
+        args: &[Box<ast::Node>],
+    ) -> TransformResult<(Box<dyn Expr>, Box<dyn Expr>)> {
+        let args = Args::bind(typ, &["expr", "count"], args)?;
+        let expr = self.transform_expr(model, rng, args.required("expr")?)?;
+        let count = self.transform_expr(model, rng, args.required("count")?)?;
+
+        Ok((expr, count))
+    }
+
+    fn transform_packed(
+        &mut self,
+        model: &Model,
+        rng: &mut CrateRng,
+        fields: &[(String, u32, Box<ast::Node>)],
+    ) -> TransformResult<Box<dyn Expr>> {
+        let mut total_width = 0;
+        let mut field_exprs = Vec::new();
+
+        for (name, width, node) in fields {
+            if *width == 0 || *width > 32 {
+                return Err(TransformError::new(format!(
+                    "Expected a width in [1, 32] for field '{}' but found {}",
+                    name, width
+                )));
+            }
+
+            if field_exprs.iter().any(|(field, _, _)| field == name) {
+                return Err(TransformError::new(format!(
+                    "Field '{}' defined more than once in Packed",
+                    name
+                )));
+            }
+
+            total_width += width;
+            if total_width > 32 {
+                return Err(TransformError::new(format!(
+                    "Expected Packed fields to total at most 32 bits but field '{}' ends at bit {}",
+                    name, total_width
+                )));
+            }
+
+            if let Some(value) = self.constant(node) {
+                let truncated = value & width_mask(*width);
+                if truncated != value {
+                    self.warnings.push(format!(
+                        "The value 0x{:x} of field '{}' does not fit in {} bits and was truncated to 0x{:x}",
+                        value, name, width, truncated
+                    ));
+                    let expr: Box<dyn Expr> = Box::new(Value::new(truncated));
+                    field_exprs.push((name.clone(), *width, expr));
+                    continue;
+                }
+            } else if let Some(max) = self.max_value(node) {
+                if max & !width_mask(*width) != 0 {
+                    return Err(TransformError::new(format!(
+                        "The value 0x{:x} of field '{}' does not fit in {} bits",
+                        max, name, width
+                    )));
+                }
+            }
+
+            // Corruptions such as flip are limited to the field rather than the variable
+            let bits = std::mem::replace(&mut self.bits, *width);
+            let expr = self.transform_expr(model, rng, node);
+            self.bits = bits;
+
+            field_exprs.push((name.clone(), *width, expr?));
+        }
+
+        Ok(Box::new(Packed::new(field_exprs)))
+    }
+
+    /// Adds a `<variable>::<field>` variable for each field of a Packed variable
+    ///
+    /// Each yields the field from the most recent value of the Packed variable.
+    fn transform_field_views(
+        &mut self,
+        model: &mut Model,
+        name: &str,
+        variable_index: usize,
+        fields: &[(String, u32, Box<ast::Node>)],
+    ) {
+        let variable = Rc::downgrade(model.get_variable_by_index(variable_index).unwrap());
+        let mut shift: u32 = fields.iter().map(|&(_, width, _)| width).sum();
+
+        for (field, width, _) in fields {
+            shift -= width;
+
+            let prev = Box::new(Prev::new(name, variable.clone()));
+            let shifted = Box::new(Binary::new(
+                prev,
+                ast::BinaryOpcode::Shr,
+                Box::new(Value::new(shift)),
+            ));
+            let view = Box::new(Binary::new(
+                shifted,
+                ast::BinaryOpcode::And,
+                Box::new(Value::new(width_mask(*width))),
+            ));
+
+            let full_name = format!("{}::{}", name, field);
+            let view = Rc::new(RefCell::new(Box::new(Variable::new(view, self.seed.to_rng()))));
+            let view_index = model.add_variable(&full_name, view);
+            self.symbols.insert_variable(&full_name, view_index);
+        }
+    }
+
+    /// Returns the largest value a Number, EnumMember, constant Range, or constant set can yield
+    fn max_value(&self, node: &ast::Node) -> Option<u32> {
+        if let Some(value) = self.constant(node) {
+            return Some(value);
+        }
+
+        match *node {
+            ast::Node::Type(ast::Type::Range, ref args) => {
+                let l = self.constant(&args[0])?;
+                let r = self.constant(&args[1])?;
+                Some(l.max(r))
+            }
+            ast::Node::Weighted(_, ref entries) => entries
+                .iter()
+                .map(|entry| match **entry {
+                    ast::Node::WeightedSample(_, ref node) => self.max_value(node),
+                    _ => None,
+                })
+                .collect::<Option<Vec<u32>>>()?
+                .into_iter()
+                .max(),
+            _ => None,
+        }
+    }
+
+    /// Collects values `node` is known to return at transform time
+    ///
+    /// Constants, constant range limits, and the values of weighted samples are collected.
+    fn known_values(&self, node: &ast::Node, values: &mut Vec<u32>) {
+        if let Some(value) = self.constant(node) {
+            values.push(value);
+            return;
+        }
+
+        match *node {
+            ast::Node::Type(ast::Type::Range, ref args) => {
+                values.extend(self.constant(&args[0]));
+                values.extend(self.constant(&args[1]));
+            }
+            ast::Node::Weighted(_, ref entries) => {
+                for entry in entries {
+                    if let ast::Node::WeightedSample(_, ref node) = **entry {
+                        self.known_values(node, values);
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Collects the states a Markov chain can reach through `node` that are known at transform time
+    ///
+    /// States are collected as inclusive intervals in signed order for signed variables.  Only
+    /// constants, ranges with constant limits, and constant samples of weighted lists are
+    /// collected.
+    fn constant_states(&self, node: &ast::Node, states: &mut Vec<(u32, u32)>) {
+        if let Some(value) = self.constant(node) {
+            states.push((value, value));
+            return;
+        }
+
+        match *node {
+            ast::Node::Type(ast::Type::Range, ref args) => {
+                if let (Some(l), Some(r)) = (self.constant(&args[0]), self.constant(&args[1])) {
+                    let offset = if self.signed { SIGN_BIT } else { 0 };
+                    let (l, r) = if r ^ offset > l ^ offset { (l, r) } else { (r, l) };
+                    states.push((l, r));
+                }
+            }
+            ast::Node::Weighted(_, ref entries) => {
+                for entry in entries {
+                    if let ast::Node::WeightedSample(_, ref node) = **entry {
+                        self.constant_states(node, states);
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Returns the value of a Number or EnumMember node
+    fn constant(&self, node: &ast::Node) -> Option<u32> {
+        match *node {
+            ast::Node::Number(value) => Some(value),
+            ast::Node::UnaryOperation(ast::UnaryOpcode::Neg, ref value) => {
+                self.constant(value).map(u32::wrapping_neg)
+            }
+            ast::Node::RIdentifier(ref name, ast::VariableMethod::Next) => {
+                match self.symbols.get(name) {
+                    Some(Symbol::EnumMember(value)) => Some(*value),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn transform_type(
+        &mut self,
+        model: &Model,
+        rng: &mut CrateRng,
+        typ: &ast::Type,
+        args: &[Box<ast::Node>],
+    ) -> TransformResult<Box<dyn Expr>> {
+        match *typ {
+            ast::Type::Pattern => Ok(Box::new(Pattern::new(self.transform_args(
+                model,
+                rng,
+                args,
+            )?))),
+            ast::Type::Sequence => self.transform_sequence(model, rng, args),
+            ast::Type::Range => self.transform_range(model, rng, args, &[]),
+            ast::Type::Cyclic => {
+                let l = self.evaluate(model, rng, &args[0])?;
+                let r = self.evaluate(model, rng, &args[1])?;
+
+                if self.signed {
+                    Ok(Box::new(Cyclic::new_signed(l, r, rng)))
+                } else {
+                    Ok(Box::new(Cyclic::new(l, r, rng)))
+                }
+            }
+            ast::Type::Where => {
+                let expr = self.transform_expr(model, rng, &args[0])?;
+
+                let value = Rc::new(Cell::new(0));
+                let shadowed = self.symbols.insert_placeholder("_", Rc::clone(&value));
+                let predicate = self.transform_expr(model, rng, &args[1]);
+                self.symbols.restore("_", shadowed);
+                let predicate = predicate?;
+
+                let limit = if args.len() == 3 {
+                    self.evaluate(model, rng, &args[2])?
+                } else {
+                    Where::DEFAULT_LIMIT
+                };
+
+                if limit == 0 {
+                    return Err(TransformError::new(
+                        "Expected a non-zero retry limit for Where".to_owned(),
+                    ));
+                }
+
+                let constant = !any_node(&args[1], &|node| match *node {
+                    ast::Node::UnaryOperation(..) | ast::Node::BinaryOperation(..) => false,
+                    _ => self.constant(node).is_none(),
+                });
+                if constant && predicate.clone().next(rng) == 0 {
+                    return Err(TransformError::new(format!(
+                        "Expected a predicate that accepts some values for Where but found `{}`",
+                        predicate
+                    )));
+                }
+
+                Ok(Box::new(Where::new(expr, predicate, value, limit)))
+            }
+            ast::Type::Burst => {
+                let args = Args::bind("Burst", &["on", "off", "active", "idle"], args)?;
+
+                let on = self.max_value(args.required("on")?);
+                let off = self.max_value(args.required("off")?);
+                if (on, off) == (Some(0), Some(0)) {
+                    return Err(TransformError::new(
+                        "Expected a non-zero on or off length for Burst but both are 0".to_owned(),
+                    ));
+                }
+
+                Ok(Box::new(Burst::new(
+                    self.transform_expr(model, rng, args.required("on")?)?,
+                    self.transform_expr(model, rng, args.required("off")?)?,
+                    self.transform_expr(model, rng, args.required("active")?)?,
+                    self.transform_expr(model, rng, args.required("idle")?)?,
+                )))
+            }
+            ast::Type::Walk => {
+                let args = Args::bind("Walk", &["start", "step", "lo", "hi", "boundary"], args)?;
+
+                let start = self.transform_expr(model, rng, args.required("start")?)?;
+                let step = self.transform_expr(model, rng, args.required("step")?)?;
+                let lo = self.evaluate(model, rng, args.required("lo")?)?;
+                let hi = self.evaluate(model, rng, args.required("hi")?)?;
+                let boundary = match args.get("boundary") {
+                    None => Boundary::Wrap,
+                    Some(&ast::Node::RIdentifier(ref name, ast::VariableMethod::Next))
+                        if name == "wrap" => Boundary::Wrap,
+                    Some(&ast::Node::RIdentifier(ref name, ast::VariableMethod::Next))
+                        if name == "reflect" => Boundary::Reflect,
+                    Some(node) => {
+                        return Err(TransformError::new(format!(
+                            "Expected wrap or reflect as the boundary of Walk but found {:?}",
+                            node
+                        )));
+                    }
+                };
+
+                if self.signed {
+                    Ok(Box::new(Walk::new_signed(start, step, lo, hi, boundary)))
+                } else {
+                    Ok(Box::new(Walk::new(start, step, lo, hi, boundary)))
+                }
+            }
+            ast::Type::Near => {
+                let args = Args::bind("Near", &["base", "radius"], args)?;
+                let base = self.transform_expr(model, rng, args.required("base")?)?;
+                let radius = self.transform_expr(model, rng, args.required("radius")?)?;
+
+                if self.signed {
+                    Ok(Box::new(Near::new_signed(base, radius)))
+                } else {
+                    Ok(Box::new(Near::new(base, radius)))
+                }
+            }
+            ast::Type::WalkingOnes => {
+                let args = Args::bind("WalkingOnes", &["width"], args)?;
+                let width = self.transform_width(model, rng, &args)?;
+
+                Ok(Box::new(WalkingOnes::new(width)))
+            }
+            ast::Type::WalkingZeros => {
+                let args = Args::bind("WalkingZeros", &["width"], args)?;
+                let width = self.transform_width(model, rng, &args)?;
+
+                Ok(Box::new(WalkingZeros::new(width)))
+            }
+            ast::Type::Gray => {
+                let args = Args::bind("Gray", &["width"], args)?;
+                let width = self.transform_width(model, rng, &args)?;
+
+                Ok(Box::new(Gray::new(width)))
+            }
+            ast::Type::Checkerboard => {
+                let args = Args::bind("Checkerboard", &["width"], args)?;
+                let width = self.transform_width(model, rng, &args)?;
+
+                Ok(Box::new(Checkerboard::new(width)))
+            }
+            ast::Type::Lfsr => {
+                let args = Args::bind("Lfsr", &["width", "taps", "seed"], args)?;
+                let width = self.transform_width(model, rng, &args)?;
+                let mask = width_mask(width);
+
+                let taps = self.evaluate(model, rng, args.required("taps")?)?;
+                if taps & !mask != 0 || taps & (1 << (width - 1)) == 0 {
+                    return Err(TransformError::new(format!(
+                        "Expected Lfsr taps within {} bits that include bit {} but found 0x{:x}",
+                        width,
+                        width - 1,
+                        taps
+                    )));
+                }
+
+                let seed = match args.get("seed") {
+                    Some(seed) => self.evaluate(model, rng, seed)? & mask,
+                    None => 1,
+                };
+                if seed == 0 {
+                    return Err(TransformError::new(
+                        "Expected a non-zero Lfsr seed".to_owned(),
+                    ));
+                }
+
+                Ok(Box::new(Lfsr::new(width, taps, seed)))
+            }
+            ast::Type::RandomBits => {
+                let args = Args::bind("RandomBits", &["width", "ones"], args)?;
+                let width = self.transform_width(model, rng, &args)?;
+                if let Some(ones) = self.max_value(args.required("ones")?) {
+                    if ones > width {
+                        return Err(TransformError::new(format!(
+                            "Expected at most {} ones for RandomBits but found {}",
+                            width, ones
+                        )));
+                    }
+                }
+                let ones = self.transform_expr(model, rng, args.required("ones")?)?;
+
+                Ok(Box::new(RandomBits::new(width, ones)))
+            }
+            ast::Type::Combo => {
+                let args = Args::bind("Combo", &["flags", "min", "max"], args)?;
+                let flags = self.transform_flags(args.required("flags")?)?;
+
+                let min = args.get("min").map_or(Some(0), |min| self.constant(min));
+                let max = args.get("max").map_or(Some(flags.len() as u32), |max| self.constant(max));
+                if let Some(max) = max {
+                    if max as usize > flags.len() {
+                        return Err(TransformError::new(format!(
+                            "Expected a max of at most {} for Combo but found {}",
+                            flags.len(),
+                            max
+                        )));
+                    }
+                }
+                if let (Some(min), Some(max)) = (min, max) {
+                    if min > max {
+                        return Err(TransformError::new(format!(
+                            "Expected min <= max for Combo but found min {} and max {}",
+                            min, max
+                        )));
+                    }
+                }
+
+                let min: Box<dyn Expr> = match args.get("min") {
+                    Some(min) => self.transform_expr(model, rng, min)?,
+                    None => Box::new(Value::new(0)),
+                };
+                let max: Box<dyn Expr> = match args.get("max") {
+                    Some(max) => self.transform_expr(model, rng, max)?,
+                    None => Box::new(Value::new(flags.len() as u32)),
+                };
+
+                Ok(Box::new(Combo::new(flags, min, max)))
+            }
+            ast::Type::Repeat => {
+                let (expr, count) = self.transform_period(model, rng, "Repeat", args)?;
+                Ok(Box::new(Repeat::new(expr, count)))
+            }
+            ast::Type::Take => {
+                let (expr, count) = self.transform_period(model, rng, "Take", args)?;
+                Ok(Box::new(Take::new(expr, count)))
+            }
+            ast::Type::Skip => {
+                let (expr, count) = self.transform_period(model, rng, "Skip", args)?;
+                Ok(Box::new(Skip::new(expr, count)))
+            }
+            ast::Type::Cycle => {
+                let (expr, count) = self.transform_period(model, rng, "Cycle", args)?;
+                Ok(Box::new(Cycle::new(expr, count)))
+            }
+            ast::Type::NoRepeat => {
+                let args = Args::bind("NoRepeat", &["expr", "window"], args)?;
+                let window = self.evaluate(model, rng, args.required("window")?)?;
+                if window == 0 {
+                    return Err(TransformError::new(
+                        "Expected a non-zero window for NoRepeat".to_owned(),
+                    ));
+                }
+
+                let source = self.transform_no_repeat_source(
+                    model,
+                    rng,
+                    args.required("expr")?,
+                    window,
+                )?;
+
+                Ok(Box::new(NoRepeat::new(source, window as usize)))
+            }
+            ast::Type::Ids => {
+                self.check_unsigned("Ids")?;
+                let args = Args::bind("Ids", &["lo", "hi"], args)?;
+                let l = self.evaluate(model, rng, args.required("lo")?)?;
+                let r = self.evaluate(model, rng, args.required("hi")?)?;
+
+                Ok(Box::new(Ids::new(l, r)))
+            }
+            ast::Type::Alloc => {
+                self.check_unsigned("Alloc")?;
+                let args = Args::bind("Alloc", &["base", "size", "len", "align"], args)?;
+                let base = self.evaluate(model, rng, args.required("base")?)?;
+                let size = self.evaluate(model, rng, args.required("size")?)?;
+                let len = self.transform_expr(model, rng, args.required("len")?)?;
+                let align = match args.get("align") {
+                    Some(align) => self.evaluate(model, rng, align)?,
+                    None => 1,
+                };
+
+                if size == 0 || u64::from(base) + u64::from(size) > (1 << 32) {
+                    return Err(TransformError::new(format!(
+                        "Expected a non-zero size for Alloc that ends within 32 bits but found base 0x{:x} and size 0x{:x}",
+                        base, size
+                    )));
+                }
+                if align == 0 {
+                    return Err(TransformError::new(
+                        "Expected a non-zero align for Alloc".to_owned(),
+                    ));
+                }
+                if let Some(len) = self.constant(args.required("len")?) {
+                    if len == 0 || len > size {
+                        return Err(TransformError::new(format!(
+                            "Expected a len in [0x1, 0x{:x}] for Alloc but found 0x{:x}",
+                            size, len
+                        )));
+                    }
+                }
+
+                Ok(Box::new(Alloc::new(base, size, len, align)))
+            }
+            ast::Type::Inject(corruption) => {
+                let expr = self.transform_expr(model, rng, &args[0])?;
+                let percent = self.evaluate(model, rng, &args[1])?;
+                if percent > 100 {
+                    return Err(TransformError::new(format!(
+                        "Expected a percent in [0, 100] for Inject but found {}",
+                        percent
+                    )));
+                }
+                if let (ast::Corruption::Flip, Some(nbits)) = (corruption, self.constant(&args[2])) {
+                    if nbits == 0 || nbits > self.bits {
+                        return Err(TransformError::new(format!(
+                            "Expected a flip count in [1, {}] for Inject but found {}",
+                            self.bits, nbits
+                        )));
+                    }
+                }
+                let operand = self.transform_expr(model, rng, &args[2])?;
+
+                Ok(Box::new(Inject::new(expr, percent, corruption, operand, self.bits)))
+            }
+            ast::Type::Expand => Err(TransformError::new("Expand() must be inside {}".to_owned())),
+            ast::Type::Done => {
+                let expr = self.transform_expr(model, rng, &*args[0])?;
+                Ok(Box::new(Done::new(expr)))
+            }
+            ast::Type::Once => {
+                let expr = self.transform_expr(model, rng, &*args[0])?;
+                Ok(Box::new(Once::new(expr)))
+            }
+        }
+    }
+}
+
+fn is_excluded(value: u32, excluded: &[(u32, u32)], signed: bool) -> bool {
+    let offset = if signed { SIGN_BIT } else { 0 };
+
+    excluded
+        .iter()
+        .any(|&(l, r)| (l ^ offset..=r ^ offset).contains(&(value ^ offset)))
+}
+
+/// Returns true if `node` references the identifier `name`
+fn references(node: &ast::Node, name: &str) -> bool {
+    any_node(node, &|node| match *node {
+        ast::Node::RIdentifier(ref identifier, _) => identifier == name,
+        _ => false,
+    })
+}
+
+/// Returns true if `f` is true for `node` or any of its descendants
+fn any_node(node: &ast::Node, f: &dyn Fn(&ast::Node) -> bool) -> bool {
+    let any = |nodes: &[Box<ast::Node>]| nodes.iter().any(|node| any_node(node, f));
+
+    if f(node) {
+        return true;
+    }
+
+    match *node {
+        ast::Node::RIdentifier(_, _) | ast::Node::Number(_) => false,
+        ast::Node::UnaryOperation(_, ref a) => any_node(a, f),
+        ast::Node::BinaryOperation(ref a, _, ref b) => any_node(a, f) || any_node(b, f),
+        ast::Node::Variable(_, ref a) | ast::Node::Input(_, ref a) => any_node(a, f),
+        ast::Node::Width(_, ref a) => any_node(a, f),
+        ast::Node::DonePolicy(ref a, _, _) => any_node(a, f),
+        ast::Node::Enum(_, ref members) | ast::Node::Flags(_, ref members) => any(members),
+        ast::Node::EnumMember(_, ref value) => value.iter().any(|value| any_node(value, f)),
+        ast::Node::Type(_, ref args) | ast::Node::Call(_, ref args) => any(args),
+        ast::Node::Weighted(_, ref args) => any(args),
+        ast::Node::Except(ref a, ref excluded) => any_node(a, f) || any(excluded),
+        ast::Node::WeightedSample(_, ref a) | ast::Node::Lookup(_, _, ref a) => any_node(a, f),
+        ast::Node::Constraint(_, ref members) => any(members),
+        ast::Node::NamedArg(_, ref a) => any_node(a, f),
+        ast::Node::Packed(ref fields) => fields.iter().any(|(_, _, a)| any_node(a, f)),
+        ast::Node::Markov(ref args, ref transitions) => {
+            any(args)
+                || transitions
+                    .iter()
+                    .any(|(a, b)| any_node(a, f) || any_node(b, f))
         }
     }
 }
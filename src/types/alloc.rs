@@ -0,0 +1,138 @@
+use rand::Rng;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::transform::CrateRng;
+use crate::model::{Expr, ExprData};
+use crate::types::Intervals;
+
+/// Returns aligned start addresses of regions that do not overlap any allocated region
+///
+/// The address space is [base, base + size).  The length of each region is drawn from `len`.  A
+/// region is allocated until its start address is passed to `release`.  Done is set when `len` is
+/// done.  Next returns the previous value and sets exhausted when no region of the drawn length is
+/// free.
+#[derive(Clone)]
+pub struct Alloc {
+    data: ExprData,
+    base: u32,
+    size: u32,
+    len: Box<dyn Expr>,
+    align: u32,
+    free: Intervals,
+    /// Allocated regions as start address to length
+    regions: BTreeMap<u32, u32>,
+    region_len: u32,
+    exhausted: bool,
+}
+
+impl Alloc {
+    pub fn new(base: u32, size: u32, len: Box<dyn Expr>, align: u32) -> Alloc {
+        Alloc {
+            data: Default::default(),
+            base,
+            size,
+            len,
+            align,
+            free: Intervals::new(base, base + (size - 1)),
+            regions: BTreeMap::new(),
+            region_len: 0,
+            exhausted: false,
+        }
+    }
+
+    /// Returns the first aligned start address and the number of aligned start addresses for
+    /// regions of `len` in each free interval
+    fn starts(&self, len: u64) -> Vec<(u64, u64)> {
+        let align = u64::from(self.align);
+
+        self.free
+            .iter()
+            .filter_map(|&(l, r)| {
+                let first = u64::from(l).div_ceil(align) * align;
+                let end = u64::from(r) + 1;
+
+                if first + len <= end {
+                    Some((first, (end - len - first) / align + 1))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+impl Expr for Alloc {
+    /// # Panics
+    ///
+    /// * If `len` returns 0
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let len = self.len.next(rng);
+        if len == 0 {
+            panic!(
+                "the len sub-expression `{}` returned 0 in the expression `{}`",
+                self.len, self
+            );
+        }
+
+        let starts = self.starts(u64::from(len));
+        let total: u64 = starts.iter().map(|&(_, count)| count).sum();
+
+        self.exhausted = total == 0;
+        self.data.done = self.len.done();
+        if self.exhausted {
+            self.region_len = 0;
+            return self.data.prev;
+        }
+
+        let mut n = rng.gen_range(0, total);
+        let mut start = 0;
+        for (first, count) in starts {
+            if n < count {
+                start = (first + n * u64::from(self.align)) as u32;
+                break;
+            }
+            n -= count;
+        }
+
+        self.free.remove(start, start + (len - 1));
+        self.regions.insert(start, len);
+        self.region_len = len;
+
+        self.data.prev = start;
+
+        self.data.prev
+    }
+
+    fn release(&mut self, value: u32) -> bool {
+        match self.regions.remove(&value) {
+            Some(len) => {
+                self.free.insert(value, value + (len - 1));
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn region_len(&self) -> u32 {
+        self.region_len
+    }
+
+    fn exhausted(&self) -> bool {
+        self.exhausted
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Alloc {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Alloc(0x{:x}, 0x{:x}, {}, 0x{:x})",
+            self.base, self.size, self.len, self.align
+        )
+    }
+}
@@ -0,0 +1,270 @@
+use crate::transform::CrateRng;
+use crate::model::{Expr, ExprData};
+
+use rand::seq::index;
+use std::fmt;
+
+/// Returns a mask of the `width` least significant bits
+pub fn width_mask(width: u32) -> u32 {
+    (((1u64) << width) - 1) as u32
+}
+
+/// Sets a single bit, walking from the least significant bit to bit `width - 1`
+#[derive(Clone)]
+pub struct WalkingOnes {
+    data: ExprData,
+    width: u32,
+    index: u32,
+}
+
+impl WalkingOnes {
+    pub fn new(width: u32) -> WalkingOnes {
+        WalkingOnes {
+            data: Default::default(),
+            width,
+            index: 0,
+        }
+    }
+}
+
+impl Expr for WalkingOnes {
+    fn next(&mut self, _rng: &mut CrateRng) -> u32 {
+        self.data.prev = 1 << self.index;
+        self.index += 1;
+
+        self.data.done = self.index == self.width;
+        if self.data.done {
+            self.index = 0;
+        }
+
+        self.data.prev
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for WalkingOnes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "WalkingOnes({})", self.width)
+    }
+}
+
+/// Clears a single bit of a `width`-bit mask, walking from the least significant bit
+#[derive(Clone)]
+pub struct WalkingZeros {
+    data: ExprData,
+    ones: WalkingOnes,
+}
+
+impl WalkingZeros {
+    pub fn new(width: u32) -> WalkingZeros {
+        WalkingZeros {
+            data: Default::default(),
+            ones: WalkingOnes::new(width),
+        }
+    }
+}
+
+impl Expr for WalkingZeros {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        self.data.prev = !self.ones.next(rng) & width_mask(self.ones.width);
+        self.data.done = self.ones.done();
+
+        self.data.prev
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for WalkingZeros {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "WalkingZeros({})", self.ones.width)
+    }
+}
+
+/// Counts through all `width`-bit values in Gray code order
+#[derive(Clone)]
+pub struct Gray {
+    data: ExprData,
+    width: u32,
+    index: u64,
+}
+
+impl Gray {
+    pub fn new(width: u32) -> Gray {
+        Gray {
+            data: Default::default(),
+            width,
+            index: 0,
+        }
+    }
+}
+
+impl Expr for Gray {
+    fn next(&mut self, _rng: &mut CrateRng) -> u32 {
+        self.data.prev = (self.index ^ (self.index >> 1)) as u32;
+        self.index += 1;
+
+        self.data.done = self.index == 1 << self.width;
+        if self.data.done {
+            self.index = 0;
+        }
+
+        self.data.prev
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Gray {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Gray({})", self.width)
+    }
+}
+
+/// A Galois linear-feedback shift register
+///
+/// Yields `seed` first.  Done is set on the last state before the register returns to `seed`.
+/// The period is 2^`width` - 1 if `taps` is a maximal-length polynomial.
+///
+/// `taps` must include bit `width - 1` so that every state has exactly one predecessor and the
+/// register is guaranteed to return to `seed`.
+#[derive(Clone)]
+pub struct Lfsr {
+    data: ExprData,
+    width: u32,
+    taps: u32,
+    seed: u32,
+    state: u32,
+}
+
+impl Lfsr {
+    pub fn new(width: u32, taps: u32, seed: u32) -> Lfsr {
+        Lfsr {
+            data: Default::default(),
+            width,
+            taps,
+            seed,
+            state: seed,
+        }
+    }
+}
+
+impl Expr for Lfsr {
+    fn next(&mut self, _rng: &mut CrateRng) -> u32 {
+        self.data.prev = self.state;
+
+        let feedback = if self.state & 1 == 1 { self.taps } else { 0 };
+        self.state = (self.state >> 1) ^ feedback;
+        self.data.done = self.state == self.seed;
+
+        self.data.prev
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Lfsr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Lfsr({}, 0x{:x}, 0x{:x})", self.width, self.taps, self.seed)
+    }
+}
+
+/// Alternates between `0b...0101` and `0b...1010`
+#[derive(Clone)]
+pub struct Checkerboard {
+    data: ExprData,
+    width: u32,
+}
+
+impl Checkerboard {
+    pub fn new(width: u32) -> Checkerboard {
+        Checkerboard {
+            data: Default::default(),
+            width,
+        }
+    }
+}
+
+impl Expr for Checkerboard {
+    fn next(&mut self, _rng: &mut CrateRng) -> u32 {
+        // The second value of the period is the last value of the period
+        let second = self.data.prev == 0x5555_5555 & width_mask(self.width);
+        let pattern = if second { 0xaaaa_aaaa } else { 0x5555_5555 };
+
+        self.data.prev = pattern & width_mask(self.width);
+        self.data.done = second;
+
+        self.data.prev
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Checkerboard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Checkerboard({})", self.width)
+    }
+}
+
+/// A random `width`-bit value with exactly `ones` bits set
+///
+/// Done is set on every value.
+#[derive(Clone)]
+pub struct RandomBits {
+    data: ExprData,
+    width: u32,
+    ones: Box<dyn Expr>,
+}
+
+impl RandomBits {
+    pub fn new(width: u32, ones: Box<dyn Expr>) -> RandomBits {
+        RandomBits {
+            data: Default::default(),
+            width,
+            ones,
+        }
+    }
+}
+
+impl Expr for RandomBits {
+    /// # Panics
+    ///
+    /// * If `ones` is greater than `width`
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let ones = self.ones.next(rng);
+        if ones > self.width {
+            panic!(
+                "the ones sub-expression `{}` returned {} which exceeds the width in the expression `{}`",
+                self.ones, ones, self
+            );
+        }
+
+        self.data.prev = index::sample(rng, self.width as usize, ones as usize)
+            .iter()
+            .fold(0, |value, bit| value | (1 << bit));
+        self.data.done = true;
+
+        self.data.prev
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for RandomBits {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "RandomBits({}, {})", self.width, self.ones)
+    }
+}
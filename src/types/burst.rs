@@ -0,0 +1,107 @@
+use crate::transform::CrateRng;
+use crate::model::{Expr, ExprData};
+
+use std::fmt;
+
+/// Alternates between bursts of active values and gaps of idle values
+///
+/// Each period yields `on` values from `active` followed by `off` values from `idle`.  `on` and
+/// `off` are redrawn at the start of every period.  Done is set on the last value of a period.
+///
+/// A period where `on` and `off` are both 0 is empty and is skipped by redrawing the lengths.
+#[derive(Clone)]
+pub struct Burst {
+    data: ExprData,
+    on: Box<dyn Expr>,
+    off: Box<dyn Expr>,
+    active: Box<dyn Expr>,
+    idle: Box<dyn Expr>,
+    /// Lengths of the on and off phases of the current period
+    lengths: Option<(u64, u64)>,
+    /// Number of values yielded in the current period
+    position: u64,
+}
+
+impl Burst {
+    /// Maximum number of consecutive empty periods before giving up
+    pub const EMPTY_LIMIT: u32 = 1000;
+
+    pub fn new(
+        on: Box<dyn Expr>,
+        off: Box<dyn Expr>,
+        active: Box<dyn Expr>,
+        idle: Box<dyn Expr>,
+    ) -> Burst {
+        Burst {
+            data: Default::default(),
+            on,
+            off,
+            active,
+            idle,
+            lengths: None,
+            position: 0,
+        }
+    }
+
+    /// Draws the lengths of the next non-empty period
+    fn draw_lengths(&mut self, rng: &mut CrateRng) -> (u64, u64) {
+        for _ in 0..Burst::EMPTY_LIMIT {
+            let lengths = (u64::from(self.on.next(rng)), u64::from(self.off.next(rng)));
+            if lengths != (0, 0) {
+                return lengths;
+            }
+        }
+
+        panic!(
+            "the on and off lengths of `{}` were both 0 for {} consecutive periods",
+            self,
+            Burst::EMPTY_LIMIT
+        );
+    }
+}
+
+impl Expr for Burst {
+    /// # Panics
+    ///
+    /// * If `on` and `off` are both 0 for `EMPTY_LIMIT` consecutive periods
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let (on, off) = match self.lengths {
+            Some(lengths) => lengths,
+            None => {
+                let lengths = self.draw_lengths(rng);
+                self.position = 0;
+                self.lengths = Some(lengths);
+
+                lengths
+            }
+        };
+
+        self.data.prev = if self.position < on {
+            self.active.next(rng)
+        } else {
+            self.idle.next(rng)
+        };
+
+        self.position += 1;
+        self.data.done = self.position == on + off;
+        if self.data.done {
+            self.lengths = None;
+        }
+
+        self.data.prev
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Burst {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Burst(on = {}, off = {}, active = {}, idle = {})",
+            self.on, self.off, self.active, self.idle
+        )
+    }
+}
@@ -0,0 +1,54 @@
+use crate::transform::CrateRng;
+use crate::model::{Expr, ExprData, Function};
+
+use std::fmt;
+
+/// Returns the value of a host function called with the values of `args`
+///
+/// The function is called on every draw.  Done is set when all `args` are done.
+#[derive(Clone)]
+pub struct Call {
+    data: ExprData,
+    name: String,
+    function: Function,
+    args: Vec<Box<dyn Expr>>,
+}
+
+impl Call {
+    pub fn new(name: &str, function: Function, args: Vec<Box<dyn Expr>>) -> Call {
+        Call {
+            data: Default::default(),
+            name: name.into(),
+            function,
+            args,
+        }
+    }
+}
+
+impl Expr for Call {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let args: Vec<u32> = self.args.iter_mut().map(|arg| arg.next(rng)).collect();
+
+        self.data.prev = self.function.call(&args);
+        self.data.done = self.args.iter().all(|arg| arg.done());
+
+        self.data.prev
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Call {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}(", self.name)?;
+        for (i, arg) in self.args.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", arg)?;
+        }
+        write!(f, ")")
+    }
+}
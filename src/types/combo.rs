@@ -0,0 +1,72 @@
+use crate::transform::CrateRng;
+use crate::model::{Expr, ExprData};
+
+use rand::Rng;
+use rand::seq::index;
+use std::fmt;
+
+/// A random combination of flags
+///
+/// Each value is the bitwise OR of a random subset of `flags`.  The size of the subset is drawn
+/// uniformly from [`min`, `max`] inclusive.  Done is set on every value.
+#[derive(Clone)]
+pub struct Combo {
+    data: ExprData,
+    flags: Vec<u32>,
+    min: Box<dyn Expr>,
+    max: Box<dyn Expr>,
+}
+
+impl Combo {
+    pub fn new(flags: Vec<u32>, min: Box<dyn Expr>, max: Box<dyn Expr>) -> Combo {
+        Combo {
+            data: Default::default(),
+            flags,
+            min,
+            max,
+        }
+    }
+}
+
+impl Expr for Combo {
+    /// # Panics
+    ///
+    /// * If `min` is greater than `max`
+    /// * If `max` is greater than the number of flags
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let min = self.min.next(rng);
+        let max = self.max.next(rng);
+
+        if min > max || max as usize > self.flags.len() {
+            panic!(
+                "expected 0 <= min <= max <= {} but found min {} and max {} in the expression `{}`",
+                self.flags.len(),
+                min,
+                max,
+                self
+            );
+        }
+
+        let count = rng.gen_range(min, max + 1) as usize;
+        self.data.prev = index::sample(rng, self.flags.len(), count)
+            .iter()
+            .fold(0, |value, index| value | self.flags[index]);
+        self.data.done = true;
+
+        self.data.prev
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Combo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Combo({{")?;
+        for flag in &self.flags {
+            write!(f, "0x{:x}, ", flag)?;
+        }
+        write!(f, "}}, {}, {})", self.min, self.max)
+    }
+}
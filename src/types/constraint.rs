@@ -0,0 +1,235 @@
+use crate::transform::CrateRng;
+use crate::model::{Expr, ExprData};
+use super::Intervals;
+
+use rand::Rng;
+use rand::seq::SliceRandom;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::Rc;
+
+/// Finds joint values for the fields of a `constraint` block
+///
+/// Each field has a finite domain.  Constraints are expressions over the fields that must
+/// evaluate to non-zero.
+///
+/// The solver works in two phases:
+///
+/// * Domain propagation.  Constraints over a single field with a domain of at most
+///   `ENUMERATE_LIMIT` values are applied to the domain once at construction.
+/// * Randomized backtracking search.  Fields are assigned in declaration order.  Each constraint is
+///   checked as soon as the last field it references is assigned.  Small domains are enumerated in
+///   random order.  Large domains are probed randomly up to `PROBE_LIMIT` times.
+///
+/// Solutions are biased.  They are not drawn uniformly from the set of all solutions.  Each field
+/// is drawn uniformly from the values that are consistent with the fields declared before it.
+/// Values of early fields that admit few solutions are therefore over-represented.
+///
+/// Constraints are assumed to be deterministic functions of the fields.
+pub struct Solver {
+    name: String,
+    fields: Vec<String>,
+    domains: Vec<Intervals>,
+    values: Vec<Rc<Cell<u32>>>,
+    /// Constraints indexed by the last field they reference
+    constraints: Vec<Vec<Box<dyn Expr>>>,
+    /// Fields that have been read since the current solution was found
+    consumed: Vec<bool>,
+    rng: CrateRng,
+}
+
+pub type SolverRef = Rc<RefCell<Solver>>;
+
+impl Solver {
+    /// Domains at or below this size are enumerated exhaustively
+    pub const ENUMERATE_LIMIT: u64 = 4096;
+
+    /// Number of random values tried for a field with a domain above `ENUMERATE_LIMIT`
+    pub const PROBE_LIMIT: u32 = 256;
+
+    /// Number of constraint evaluations allowed per solution
+    pub const STEP_LIMIT: u32 = 1_000_000;
+
+    /// `constraints` pairs each constraint with the indexes of the fields it references
+    pub fn new(
+        name: &str,
+        fields: Vec<String>,
+        domains: Vec<Intervals>,
+        values: Vec<Rc<Cell<u32>>>,
+        constraints: Vec<(Vec<usize>, Box<dyn Expr>)>,
+        rng: CrateRng,
+    ) -> Solver {
+        let mut solver = Solver {
+            name: name.into(),
+            consumed: vec![true; fields.len()],
+            constraints: (0..fields.len()).map(|_| Vec::new()).collect(),
+            fields,
+            domains,
+            values,
+            rng,
+        };
+
+        for (referenced, constraint) in constraints {
+            let level = referenced.iter().cloned().max().unwrap_or(0);
+
+            if referenced.len() == 1 && solver.domains[level].len() <= Solver::ENUMERATE_LIMIT {
+                solver.restrict(level, constraint);
+            } else {
+                solver.constraints[level].push(constraint);
+            }
+        }
+
+        solver
+    }
+
+    /// Removes the values of a field that do not satisfy a constraint over only that field
+    fn restrict(&mut self, index: usize, mut constraint: Box<dyn Expr>) {
+        let domain = self.domains[index].clone();
+
+        for n in 0..domain.len() {
+            let value = domain.nth(n);
+            self.values[index].set(value);
+            if constraint.next(&mut self.rng) == 0 {
+                self.domains[index].remove(value, value);
+            }
+        }
+    }
+
+    /// Returns the name of the first field with no values left after domain propagation
+    pub fn empty_field(&self) -> Option<&str> {
+        self.domains
+            .iter()
+            .position(|domain| domain.is_empty())
+            .map(|index| self.fields[index].as_str())
+    }
+
+    /// Returns the value of a field in the current solution
+    ///
+    /// A new solution is found when a field is read a second time from the same solution.
+    pub fn next(&mut self, index: usize) -> u32 {
+        if self.consumed[index] {
+            self.solve();
+            for consumed in self.consumed.iter_mut() {
+                *consumed = false;
+            }
+        }
+
+        self.consumed[index] = true;
+        self.values[index].get()
+    }
+
+    /// Returns whether a solution is found within `STEP_LIMIT` constraint evaluations
+    ///
+    /// The solution is discarded.  The next field read finds a new solution.
+    pub fn solvable(&mut self) -> bool {
+        let mut budget = Solver::STEP_LIMIT;
+
+        self.search(0, &mut budget)
+    }
+
+    /// # Panics
+    ///
+    /// * If no solution is found within `STEP_LIMIT` constraint evaluations
+    fn solve(&mut self) {
+        let mut budget = Solver::STEP_LIMIT;
+
+        if !self.search(0, &mut budget) {
+            panic!(
+                "could not find a solution for constraint `{}` within {} steps",
+                self.name,
+                Solver::STEP_LIMIT
+            );
+        }
+    }
+
+    fn search(&mut self, index: usize, budget: &mut u32) -> bool {
+        if index == self.domains.len() {
+            return true;
+        }
+
+        let len = self.domains[index].len();
+        if len <= Solver::ENUMERATE_LIMIT {
+            let mut offsets: Vec<u64> = (0..len).collect();
+            offsets.shuffle(&mut self.rng);
+
+            for offset in offsets {
+                let value = self.domains[index].nth(offset);
+                if self.assign(index, value, budget) {
+                    return true;
+                }
+                if *budget == 0 {
+                    return false;
+                }
+            }
+        } else {
+            for _ in 0..Solver::PROBE_LIMIT {
+                let value = self.domains[index].nth(self.rng.gen_range(0, len));
+                if self.assign(index, value, budget) {
+                    return true;
+                }
+                if *budget == 0 {
+                    return false;
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Assigns a value to a field then searches the remaining fields
+    fn assign(&mut self, index: usize, value: u32, budget: &mut u32) -> bool {
+        self.values[index].set(value);
+
+        for constraint in self.constraints[index].iter_mut() {
+            if *budget == 0 {
+                return false;
+            }
+            *budget -= 1;
+
+            if constraint.next(&mut self.rng) == 0 {
+                return false;
+            }
+        }
+
+        self.search(index + 1, budget)
+    }
+}
+
+/// A single field of a `constraint` block
+///
+/// All fields of a block share a `Solver`.
+#[derive(Clone)]
+pub struct ConstraintField {
+    data: ExprData,
+    solver: SolverRef,
+    index: usize,
+}
+
+impl ConstraintField {
+    pub fn new(solver: SolverRef, index: usize) -> ConstraintField {
+        ConstraintField {
+            data: Default::default(),
+            solver,
+            index,
+        }
+    }
+}
+
+impl Expr for ConstraintField {
+    fn next(&mut self, _rng: &mut CrateRng) -> u32 {
+        self.data.prev = self.solver.borrow_mut().next(self.index);
+        self.data.done = true;
+
+        self.data.prev
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for ConstraintField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.solver.borrow().domains[self.index])
+    }
+}
@@ -0,0 +1,176 @@
+use rand::Rng;
+use std::fmt;
+
+use crate::transform::CrateRng;
+use crate::model::{Expr, ExprData};
+use crate::types::{write_signed, SIGN_BIT};
+
+/// Number of Feistel rounds used by the permutation
+const ROUNDS: usize = 4;
+
+/// Visits every value in [l, r] exactly once per period in random order
+///
+/// Equivalent to SystemVerilog `randc` but without storing a pool of values.  Each value is
+/// computed by passing an index through a keyed bijective permutation.  The permutation is a
+/// balanced Feistel network over the smallest even power-of-two domain that covers the range.
+/// Outputs that fall outside the range are cycle-walked back into the range.
+///
+/// The keys are redrawn at the end of every period.
+#[derive(Clone)]
+pub struct Cyclic {
+    data: ExprData,
+    l: u32,
+    r: u32,
+    signed: bool,
+    /// Lower limit with the sign bit flipped if signed
+    lower: u32,
+    /// Number of values in the range
+    len: u64,
+    /// Number of bits in each half of the Feistel network
+    half_bits: u32,
+    keys: [u32; ROUNDS],
+    index: u64,
+}
+
+impl Cyclic {
+    pub fn new(l: u32, r: u32, rng: &mut CrateRng) -> Cyclic {
+        Cyclic::with_sign(l, r, false, rng)
+    }
+
+    /// Creates a cyclic range whose limits are two's complement signed values
+    pub fn new_signed(l: u32, r: u32, rng: &mut CrateRng) -> Cyclic {
+        Cyclic::with_sign(l, r, true, rng)
+    }
+
+    fn with_sign(l: u32, r: u32, signed: bool, rng: &mut CrateRng) -> Cyclic {
+        let offset = if signed { SIGN_BIT } else { 0 };
+        let (ol, or) = (l ^ offset, r ^ offset);
+        let limits = if or > ol { (ol, or) } else { (or, ol) };
+        let len = u64::from(limits.1 - limits.0) + 1;
+
+        let mut bits = 64 - (len - 1).leading_zeros();
+        if bits < 2 {
+            bits = 2;
+        }
+        if bits % 2 == 1 {
+            bits += 1;
+        }
+
+        let mut cyclic = Cyclic {
+            data: Default::default(),
+            l,
+            r,
+            signed,
+            lower: limits.0,
+            len,
+            half_bits: bits / 2,
+            keys: [0; ROUNDS],
+            index: 0,
+        };
+
+        cyclic.init_keys(rng);
+
+        cyclic
+    }
+
+    fn init_keys(&mut self, rng: &mut CrateRng) {
+        for key in self.keys.iter_mut() {
+            *key = rng.gen();
+        }
+    }
+
+    fn round(&self, x: u32, key: u32) -> u32 {
+        let mut x = x ^ key;
+        x = x.wrapping_mul(0x9e37_79b1);
+        x ^= x >> 15;
+        x = x.wrapping_mul(0x85eb_ca6b);
+        x ^= x >> 13;
+
+        x & self.half_mask()
+    }
+
+    fn half_mask(&self) -> u32 {
+        ((1u64 << self.half_bits) - 1) as u32
+    }
+
+    fn feistel(&self, x: u64) -> u64 {
+        let mask = self.half_mask();
+        let mut left = (x >> self.half_bits) as u32 & mask;
+        let mut right = x as u32 & mask;
+
+        for key in self.keys.iter() {
+            let next = left ^ self.round(right, *key);
+            left = right;
+            right = next;
+        }
+
+        (u64::from(left) << self.half_bits) | u64::from(right)
+    }
+
+    /// Maps an index in [0, len) to a unique offset in [0, len)
+    fn permute(&self, index: u64) -> u64 {
+        let mut x = self.feistel(index);
+        while x >= self.len {
+            x = self.feistel(x);
+        }
+
+        x
+    }
+}
+
+impl Expr for Cyclic {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let offset = if self.signed { SIGN_BIT } else { 0 };
+
+        self.data.prev = (self.lower + self.permute(self.index) as u32) ^ offset;
+        self.index += 1;
+
+        self.data.done = self.index == self.len;
+        if self.data.done {
+            self.index = 0;
+            self.init_keys(rng);
+        }
+
+        self.data.prev
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Cyclic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.signed {
+            write!(f, "Cyclic([")?;
+            write_signed(f, self.l)?;
+            write!(f, ", ")?;
+            write_signed(f, self.r)?;
+            write!(f, "])")
+        } else {
+            write!(f, "Cyclic([0x{:x}, 0x{:x}])", self.l, self.r)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transform::Seed;
+
+    #[test]
+    fn permute_is_bijective() {
+        let mut rng = Seed::from_u32(0).to_rng();
+
+        for len in &[1u32, 2, 3, 5, 16, 17, 1000] {
+            let cyclic = Cyclic::new(0, len - 1, &mut rng);
+            let mut seen = vec![false; *len as usize];
+
+            for index in 0..u64::from(*len) {
+                let offset = cyclic.permute(index) as usize;
+                assert!(!seen[offset]);
+                seen[offset] = true;
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use crate::transform::CrateRng;
+use crate::model::{Expr, ExprData};
+
+use std::cell::Cell;
+use std::fmt;
+use std::rc::Rc;
+
+/// Redraws a sub-expression until a predicate holds
+///
+/// The candidate value is made available to the predicate via `value`.  Doneness is taken from
+/// the sub-expression on the accepted draw.
+#[derive(Clone)]
+pub struct Where {
+    data: ExprData,
+    expr: Box<dyn Expr>,
+    predicate: Box<dyn Expr>,
+    value: Rc<Cell<u32>>,
+    limit: u32,
+}
+
+impl Where {
+    pub const DEFAULT_LIMIT: u32 = 1000;
+
+    pub fn new(
+        expr: Box<dyn Expr>,
+        predicate: Box<dyn Expr>,
+        value: Rc<Cell<u32>>,
+        limit: u32,
+    ) -> Where {
+        Where {
+            data: Default::default(),
+            expr,
+            predicate,
+            value,
+            limit,
+        }
+    }
+}
+
+impl Expr for Where {
+    /// # Panics
+    ///
+    /// * If the predicate rejects `limit` consecutive draws
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        for _ in 0..self.limit {
+            let value = self.expr.next(rng);
+            self.value.set(value);
+
+            if self.predicate.next(rng) != 0 {
+                self.data.prev = value;
+                self.data.done = self.expr.done();
+
+                return self.data.prev;
+            }
+        }
+
+        panic!(
+            "the predicate `{}` rejected {} consecutive values in the expression `{}`",
+            self.predicate, self.limit, self
+        );
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Where {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Where({}, {}, {})", self.expr, self.predicate, self.limit)
+    }
+}
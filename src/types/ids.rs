@@ -0,0 +1,74 @@
+use rand::Rng;
+use std::fmt;
+
+use crate::transform::CrateRng;
+use crate::model::{Expr, ExprData};
+use crate::types::Intervals;
+
+/// Returns random values in [l, r] inclusive that are not in use
+///
+/// A returned value is in use until it is passed to `release`.  Done is set when every value is in
+/// use.  Next returns the previous value and sets exhausted while every value is in use.
+#[derive(Clone)]
+pub struct Ids {
+    data: ExprData,
+    l: u32,
+    r: u32,
+    free: Intervals,
+    exhausted: bool,
+}
+
+impl Ids {
+    pub fn new(l: u32, r: u32) -> Ids {
+        Ids {
+            data: Default::default(),
+            l,
+            r,
+            free: Intervals::new(l, r),
+            exhausted: false,
+        }
+    }
+}
+
+impl Expr for Ids {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        self.exhausted = self.free.is_empty();
+        if self.exhausted {
+            self.data.done = true;
+            return self.data.prev;
+        }
+
+        let value = self.free.nth(rng.gen_range(0, self.free.len()));
+        self.free.remove(value, value);
+
+        self.data.prev = value;
+        self.data.done = self.free.is_empty();
+
+        self.data.prev
+    }
+
+    fn release(&mut self, value: u32) -> bool {
+        let (l, r) = if self.r > self.l { (self.l, self.r) } else { (self.r, self.l) };
+
+        if value < l || value > r || self.free.contains(value) {
+            false
+        } else {
+            self.free.insert(value, value);
+            true
+        }
+    }
+
+    fn exhausted(&self) -> bool {
+        self.exhausted
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Ids {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Ids(0x{:x}, 0x{:x})", self.l, self.r)
+    }
+}
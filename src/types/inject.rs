@@ -0,0 +1,107 @@
+use rand::Rng;
+use std::fmt;
+
+use crate::transform::CrateRng;
+use crate::model::{Expr, ExprData};
+
+use rvs_parser::ast::Corruption;
+
+/// Corrupts the values of `expr` `percent` percent of the time
+///
+/// A corrupted value has `nbits` random bits flipped within `bits`, is replaced by the value of
+/// the corruption sub-expression, or is XORed with the value of the corruption sub-expression.
+/// The corruption sub-expression is evaluated only when a value is corrupted.  Done is set when
+/// `expr` is done.
+#[derive(Clone)]
+pub struct Inject {
+    data: ExprData,
+    expr: Box<dyn Expr>,
+    percent: u32,
+    corruption: Corruption,
+    operand: Box<dyn Expr>,
+    bits: u32,
+    injected: bool,
+}
+
+impl Inject {
+    pub fn new(
+        expr: Box<dyn Expr>,
+        percent: u32,
+        corruption: Corruption,
+        operand: Box<dyn Expr>,
+        bits: u32,
+    ) -> Inject {
+        Inject {
+            data: Default::default(),
+            expr,
+            percent,
+            corruption,
+            operand,
+            bits,
+            injected: false,
+        }
+    }
+
+    /// Flips `nbits` distinct random bits of `value`
+    fn flip(&self, value: u32, nbits: u32, rng: &mut CrateRng) -> u32 {
+        if nbits == 0 || nbits > self.bits {
+            panic!(
+                "the flip sub-expression `{}` returned {} which is not in [1, {}] in the expression `{}`",
+                self.operand, nbits, self.bits, self
+            );
+        }
+
+        let mut positions: Vec<u32> = (0..self.bits).collect();
+        let mut value = value;
+        for i in 0..nbits as usize {
+            let j = rng.gen_range(i, positions.len());
+            positions.swap(i, j);
+            value ^= 1 << positions[i];
+        }
+
+        value
+    }
+}
+
+impl Expr for Inject {
+    /// # Panics
+    ///
+    /// * If the `flip` sub-expression returns 0 or more than the width of the variable
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let value = self.expr.next(rng);
+
+        self.injected = rng.gen_range(0, 100) < self.percent;
+        self.data.prev = if self.injected {
+            let operand = self.operand.next(rng);
+
+            match self.corruption {
+                Corruption::Flip => self.flip(value, operand, rng),
+                Corruption::Replace => operand,
+                Corruption::Xor => value ^ operand,
+            }
+        } else {
+            value
+        };
+        self.data.done = self.expr.done();
+
+        self.data.prev
+    }
+
+    fn injected(&self) -> bool {
+        self.injected
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Inject {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Inject({}, {}, {}({}))",
+            self.expr, self.percent, self.corruption, self.operand
+        )
+    }
+}
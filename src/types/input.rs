@@ -0,0 +1,44 @@
+use crate::transform::CrateRng;
+use crate::model::{Expr, ExprData};
+
+use std::fmt;
+
+/// A value set by the host with `set`
+///
+/// Done is always set.
+#[derive(Clone)]
+pub struct Input {
+    data: ExprData,
+}
+
+impl Input {
+    pub fn new(value: u32) -> Input {
+        Input {
+            data: ExprData {
+                prev: value,
+                done: true,
+            },
+        }
+    }
+}
+
+impl Expr for Input {
+    fn next(&mut self, _rng: &mut CrateRng) -> u32 {
+        self.data.prev
+    }
+
+    fn set(&mut self, value: u32) -> bool {
+        self.data.prev = value;
+        true
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "0x{:x}", self.data.prev)
+    }
+}
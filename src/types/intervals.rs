@@ -0,0 +1,163 @@
+use std::fmt;
+
+/// A set of `u32` values stored as sorted, disjoint, inclusive intervals
+///
+/// Memory is proportional to the number of intervals instead of the number of values.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Intervals {
+    intervals: Vec<(u32, u32)>,
+}
+
+impl Intervals {
+    /// Returns the set of all values in [l, r] inclusive
+    pub fn new(l: u32, r: u32) -> Intervals {
+        let limits = if r > l { (l, r) } else { (r, l) };
+
+        Intervals {
+            intervals: vec![limits],
+        }
+    }
+
+    /// Returns the number of values in the set
+    pub fn len(&self) -> u64 {
+        self.intervals
+            .iter()
+            .map(|&(l, r)| u64::from(r - l) + 1)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Returns whether `value` is in the set
+    pub fn contains(&self, value: u32) -> bool {
+        self.intervals.iter().any(|&(l, r)| l <= value && value <= r)
+    }
+
+    /// Adds all values in [l, r] inclusive to the set
+    pub fn insert(&mut self, l: u32, r: u32) {
+        let (mut l, mut r) = if r > l { (l, r) } else { (r, l) };
+        let mut intervals = Vec::with_capacity(self.intervals.len() + 1);
+
+        for &(il, ir) in &self.intervals {
+            let disjoint_below = u64::from(ir) + 1 < u64::from(l);
+            let disjoint_above = u64::from(r) + 1 < u64::from(il);
+
+            if disjoint_below || disjoint_above {
+                intervals.push((il, ir));
+            } else {
+                l = l.min(il);
+                r = r.max(ir);
+            }
+        }
+
+        intervals.push((l, r));
+        intervals.sort();
+        self.intervals = intervals;
+    }
+
+    /// Removes all values in [l, r] inclusive from the set
+    pub fn remove(&mut self, l: u32, r: u32) {
+        let (l, r) = if r > l { (l, r) } else { (r, l) };
+        let mut intervals = Vec::with_capacity(self.intervals.len() + 1);
+
+        for &(il, ir) in &self.intervals {
+            if ir < l || il > r {
+                intervals.push((il, ir));
+            } else {
+                if il < l {
+                    intervals.push((il, l - 1));
+                }
+                if ir > r {
+                    intervals.push((r + 1, ir));
+                }
+            }
+        }
+
+        self.intervals = intervals;
+    }
+
+    /// Iterates over the intervals in ascending order
+    pub fn iter(&self) -> ::std::slice::Iter<'_, (u32, u32)> {
+        self.intervals.iter()
+    }
+
+    /// Returns the `n`th smallest value in the set
+    ///
+    /// # Panics
+    ///
+    /// * If `n` >= `len()`
+    pub fn nth(&self, mut n: u64) -> u32 {
+        for &(l, r) in &self.intervals {
+            let len = u64::from(r - l) + 1;
+            if n < len {
+                return l + n as u32;
+            }
+            n -= len;
+        }
+
+        panic!("index out of bounds");
+    }
+}
+
+impl fmt::Display for Intervals {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let [(l, r)] = self.intervals[..] {
+            if l != r {
+                return write!(f, "[0x{:x}, 0x{:x}]", l, r);
+            }
+        }
+
+        write!(f, "{{")?;
+        for &(l, r) in &self.intervals {
+            if l == r {
+                write!(f, "0x{:x}, ", l)?;
+            } else {
+                write!(f, "[0x{:x}, 0x{:x}], ", l, r)?;
+            }
+        }
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remove() {
+        let mut intervals = Intervals::new(0, 255);
+        intervals.remove(3, 3);
+        intervals.remove(0x20, 0x10);
+        intervals.remove(255, 255);
+
+        assert_eq!(intervals.intervals, [(0, 2), (4, 0xf), (0x21, 254)]);
+        assert_eq!(intervals.len(), 3 + 12 + 222);
+        assert_eq!(intervals.nth(0), 0);
+        assert_eq!(intervals.nth(3), 4);
+        assert_eq!(intervals.nth(15), 0x21);
+    }
+
+    #[test]
+    fn insert() {
+        let mut intervals = Intervals::default();
+        intervals.insert(10, 12);
+        intervals.insert(0, 2);
+        intervals.insert(3, 4);
+        intervals.insert(20, 30);
+        intervals.insert(13, 19);
+
+        assert_eq!(intervals.intervals, [(0, 4), (10, 30)]);
+        assert_eq!(intervals.to_string(), "{[0x0, 0x4], [0xa, 0x1e], }");
+    }
+
+    #[test]
+    fn full_range() {
+        let mut intervals = Intervals::new(0, u32::MAX);
+        assert_eq!(intervals.len(), 1 << 32);
+
+        intervals.remove(0, u32::MAX);
+        assert!(intervals.is_empty());
+    }
+}
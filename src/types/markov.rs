@@ -0,0 +1,125 @@
+use crate::transform::CrateRng;
+use crate::model::{Expr, ExprData};
+
+use indexmap::IndexMap;
+use std::fmt;
+
+/// A Markov chain over a set of states
+///
+/// Yields the initial state first.  Each following value is drawn from the transition expression
+/// of the previous value.
+///
+/// By default, done is set on the last state before the chain returns to the initial state.  I.e.
+/// each period is one excursion from the initial state.  To do this, the transition from a state
+/// is drawn when the state is yielded instead of on the following call.
+///
+/// If a count is given, done is set every `count` values instead.  The count is re-evaluated at
+/// the start of every period.
+///
+/// The chain is not reset at the end of a period.
+#[derive(Clone)]
+pub struct Markov {
+    data: ExprData,
+    init: Box<dyn Expr>,
+    count: Option<Box<dyn Expr>>,
+    transitions: IndexMap<u32, Box<dyn Expr>>,
+    initial: u32,
+    /// The most recently yielded state
+    current: Option<u32>,
+    /// The next state when it has already been drawn to determine doneness
+    pending: Option<u32>,
+    /// Number of values yielded in the current period
+    index: u32,
+    /// Number of values in the current period
+    period: u32,
+}
+
+impl Markov {
+    pub fn new(
+        init: Box<dyn Expr>,
+        count: Option<Box<dyn Expr>>,
+        transitions: IndexMap<u32, Box<dyn Expr>>,
+        rng: &mut CrateRng,
+    ) -> Markov {
+        let mut markov = Markov {
+            data: Default::default(),
+            init,
+            count,
+            transitions,
+            initial: 0,
+            current: None,
+            pending: None,
+            index: 0,
+            period: 0,
+        };
+
+        markov.init_period(rng);
+
+        markov
+    }
+
+    fn init_period(&mut self, rng: &mut CrateRng) {
+        self.index = 0;
+        if let Some(ref mut count) = self.count {
+            self.period = count.next(rng);
+        }
+    }
+
+    /// # Panics
+    ///
+    /// * If `state` has no transition
+    fn transition(&mut self, state: u32, rng: &mut CrateRng) -> u32 {
+        match self.transitions.get_mut(&state) {
+            Some(transition) => transition.next(rng),
+            None => panic!("the state 0x{:x} has no transition in `{}`", state, self),
+        }
+    }
+}
+
+impl Expr for Markov {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let state = match (self.pending.take(), self.current) {
+            (Some(state), _) => state,
+            (None, Some(current)) => self.transition(current, rng),
+            (None, None) => {
+                self.initial = self.init.next(rng);
+                self.initial
+            }
+        };
+
+        self.current = Some(state);
+        self.data.prev = state;
+
+        if self.count.is_some() {
+            self.index += 1;
+            self.data.done = self.index >= self.period;
+            if self.data.done {
+                self.init_period(rng);
+            }
+        } else {
+            let next = self.transition(state, rng);
+            self.pending = Some(next);
+            self.data.done = next == self.initial;
+        }
+
+        self.data.prev
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Markov {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Markov({}", self.init)?;
+        if let Some(ref count) = self.count {
+            write!(f, ", {}", count)?;
+        }
+        write!(f, ") {{")?;
+        for (state, transition) in &self.transitions {
+            write!(f, "0x{:x} => {}, ", state, transition)?;
+        }
+        write!(f, "}}")
+    }
+}
@@ -3,17 +3,53 @@ mod operation;
 mod pattern;
 mod sequence;
 mod range;
+mod intervals;
+mod cyclic;
+mod filter;
+mod placeholder;
+mod constraint;
 mod weighted;
+mod markov;
+mod burst;
+mod walk;
+mod bits;
+mod combo;
+mod packed;
 mod variables;
+mod period;
+mod norepeat;
+mod ids;
+mod alloc;
+mod inject;
+mod input;
+mod call;
 mod done;
 mod once;
 
-pub use self::value::Value;
+pub use self::value::{write_signed, Value};
 pub use self::operation::{Binary, Unary};
 pub use self::pattern::Pattern;
-pub use self::sequence::Sequence;
-pub use self::range::Range;
+pub use self::sequence::{Sequence, SequenceEnd};
+pub use self::range::{DynamicRange, Range, RangeExcept, SIGN_BIT};
+pub use self::intervals::Intervals;
+pub use self::cyclic::Cyclic;
+pub use self::filter::Where;
+pub use self::placeholder::Placeholder;
+pub use self::constraint::{ConstraintField, Solver};
 pub use self::weighted::{WeightedWithReplacement, WeightedWithoutReplacement};
-pub use self::variables::{Next, Prev};
+pub use self::markov::Markov;
+pub use self::burst::Burst;
+pub use self::walk::{Boundary, Near, Walk};
+pub use self::combo::Combo;
+pub use self::packed::Packed;
+pub use self::bits::{width_mask, Checkerboard, Gray, Lfsr, RandomBits, WalkingOnes, WalkingZeros};
+pub use self::variables::{Next, Prev, PrevN};
+pub use self::period::{Cycle, Repeat, Skip, Take};
+pub use self::norepeat::{NoRepeat, NoRepeatSource};
+pub use self::ids::Ids;
+pub use self::alloc::Alloc;
+pub use self::inject::Inject;
+pub use self::input::Input;
+pub use self::call::Call;
 pub use self::done::Done;
 pub use self::once::Once;
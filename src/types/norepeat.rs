@@ -0,0 +1,149 @@
+use crate::transform::CrateRng;
+use crate::model::{Expr, ExprData};
+use crate::types::Intervals;
+
+use rand::Rng;
+use std::collections::VecDeque;
+use std::fmt;
+
+/// The values sampled by a `NoRepeat`
+#[derive(Clone)]
+pub enum NoRepeatSource {
+    /// Redraws a sub-expression until its value is not recent
+    Expr(Box<dyn Expr>),
+    /// Samples exactly from [l, r] inclusive excluding recent values
+    Range(u32, u32),
+    /// Samples exactly from (weight, value) pairs with replacement excluding recent values
+    Weighted(Vec<(u32, u32)>),
+}
+
+/// Returns a value that differs from each of the last `window` values
+///
+/// Ranges and weighted constants are sampled exactly.  Other sub-expressions are redrawn up to
+/// `RETRY_LIMIT` times.  Doneness is taken from the sub-expression on the accepted draw.  Done is
+/// set on every value for exact sampling.
+#[derive(Clone)]
+pub struct NoRepeat {
+    data: ExprData,
+    source: NoRepeatSource,
+    window: usize,
+    recent: VecDeque<u32>,
+    /// The values of a `Range` source that are not recent
+    allowed: Intervals,
+}
+
+impl NoRepeat {
+    pub const RETRY_LIMIT: u32 = 1000;
+
+    pub fn new(source: NoRepeatSource, window: usize) -> NoRepeat {
+        let allowed = match source {
+            NoRepeatSource::Range(l, r) => Intervals::new(l, r),
+            _ => Intervals::default(),
+        };
+
+        NoRepeat {
+            data: Default::default(),
+            source,
+            window,
+            recent: VecDeque::new(),
+            allowed,
+        }
+    }
+
+    fn next_expr(&mut self, rng: &mut CrateRng) -> u32 {
+        if let NoRepeatSource::Expr(ref mut expr) = self.source {
+            for _ in 0..NoRepeat::RETRY_LIMIT {
+                let value = expr.next(rng);
+
+                if !self.recent.contains(&value) {
+                    self.data.done = expr.done();
+                    return value;
+                }
+            }
+        }
+
+        panic!(
+            "could not draw a value that differs from the last {} values within {} draws in the expression `{}`",
+            self.window,
+            NoRepeat::RETRY_LIMIT,
+            self
+        );
+    }
+}
+
+impl Expr for NoRepeat {
+    /// # Panics
+    ///
+    /// * If a sub-expression draws a recent value `RETRY_LIMIT` consecutive times
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let value = match self.source {
+            NoRepeatSource::Expr(_) => self.next_expr(rng),
+            NoRepeatSource::Range(..) => {
+                self.data.done = true;
+                self.allowed.nth(rng.gen_range(0, self.allowed.len()))
+            }
+            NoRepeatSource::Weighted(ref members) => {
+                let recent = &self.recent;
+                let allowed = || members.iter().filter(|(_, value)| !recent.contains(value));
+                let total: u64 = allowed().map(|(weight, _)| u64::from(*weight)).sum();
+
+                let mut choice = rng.gen_range(0, total);
+                let mut selected = 0;
+                for (weight, value) in allowed() {
+                    if choice < u64::from(*weight) {
+                        selected = *value;
+                        break;
+                    }
+                    choice -= u64::from(*weight);
+                }
+
+                self.data.done = true;
+                selected
+            }
+        };
+
+        self.recent.push_front(value);
+        let evicted = if self.recent.len() > self.window {
+            self.recent.pop_back()
+        } else {
+            None
+        };
+
+        if let NoRepeatSource::Range(..) = self.source {
+            self.allowed.remove(value, value);
+            if let Some(evicted) = evicted {
+                self.allowed.insert(evicted, evicted);
+            }
+        }
+
+        self.data.prev = value;
+
+        self.data.prev
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for NoRepeatSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            NoRepeatSource::Expr(ref expr) => expr.fmt(f),
+            NoRepeatSource::Range(l, r) => write!(f, "[0x{:x}, 0x{:x}]", l, r),
+            NoRepeatSource::Weighted(ref members) => {
+                write!(f, "r{{")?;
+                for (weight, value) in members {
+                    write!(f, "{}: 0x{:x}, ", weight, value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+impl fmt::Display for NoRepeat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "NoRepeat({}, {})", self.source, self.window)
+    }
+}
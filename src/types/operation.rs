@@ -2,6 +2,7 @@ use crate::transform::CrateRng;
 use crate::model::{Expr, ExprData};
 use rvs_parser::ast;
 
+use std::cmp::Ordering;
 use std::fmt::{self, Write};
 use std::num::Wrapping;
 
@@ -11,6 +12,9 @@ pub struct Binary {
     operation: ast::BinaryOpcode,
     operands: (Box<dyn Expr>, Box<dyn Expr>),
     done: (bool, bool),
+    done_policy: ast::DonePolicy,
+    done_reset: ast::DoneReset,
+    signed: bool,
 }
 
 #[derive(Clone)]
@@ -27,6 +31,37 @@ impl Binary {
             operation,
             operands: (l, r),
             done: (false, false),
+            done_policy: ast::DonePolicy::All,
+            done_reset: ast::DoneReset::Sticky,
+            signed: false,
+        }
+    }
+
+    /// Selects which operands must be done for the operation to be done
+    ///
+    /// The default is all operands with sticky operand done flags.
+    pub fn with_done(mut self, policy: ast::DonePolicy, reset: ast::DoneReset) -> Binary {
+        self.done_policy = policy;
+        self.done_reset = reset;
+        self
+    }
+
+    /// Creates an operation on two's complement signed operands
+    ///
+    /// Comparisons, `>>`, `/`, and `%` are signed.  All other operations are the same as for
+    /// unsigned operands.
+    pub fn new_signed(l: Box<dyn Expr>, operation: ast::BinaryOpcode, r: Box<dyn Expr>) -> Binary {
+        Binary {
+            signed: true,
+            ..Binary::new(l, operation, r)
+        }
+    }
+
+    fn compare(&self, l: u32, r: u32) -> Ordering {
+        if self.signed {
+            (l as i32).cmp(&(r as i32))
+        } else {
+            l.cmp(&r)
         }
     }
 }
@@ -38,18 +73,37 @@ impl Expr for Binary {
 
         self.done.0 |= self.operands.0.done();
         self.done.1 |= self.operands.1.done();
-        self.data.done = self.done.0 && self.done.1;
+        self.data.done = match self.done_policy {
+            ast::DonePolicy::All => self.done.0 && self.done.1,
+            ast::DonePolicy::Any => self.done.0 || self.done.1,
+            ast::DonePolicy::Left => self.done.0,
+            ast::DonePolicy::Right => self.done.1,
+        };
+        if self.data.done && self.done_reset == ast::DoneReset::Periodic {
+            self.done = (false, false);
+        }
 
         self.data.prev = match self.operation {
+            ast::BinaryOpcode::LogicalOr => (l != 0 || r != 0) as u32,
+            ast::BinaryOpcode::LogicalAnd => (l != 0 && r != 0) as u32,
             ast::BinaryOpcode::Or => l | r,
             ast::BinaryOpcode::Xor => l ^ r,
             ast::BinaryOpcode::And => l & r,
+            ast::BinaryOpcode::Eq => (l == r) as u32,
+            ast::BinaryOpcode::Ne => (l != r) as u32,
+            ast::BinaryOpcode::Lt => (self.compare(l, r) == Ordering::Less) as u32,
+            ast::BinaryOpcode::Le => (self.compare(l, r) != Ordering::Greater) as u32,
+            ast::BinaryOpcode::Gt => (self.compare(l, r) == Ordering::Greater) as u32,
+            ast::BinaryOpcode::Ge => (self.compare(l, r) != Ordering::Less) as u32,
             ast::BinaryOpcode::Shl => (Wrapping(l) << (r as usize)).0,
+            ast::BinaryOpcode::Shr if self.signed => (Wrapping(l as i32) >> (r as usize)).0 as u32,
             ast::BinaryOpcode::Shr => (Wrapping(l) >> (r as usize)).0,
             ast::BinaryOpcode::Add => (Wrapping(l) + Wrapping(r)).0,
             ast::BinaryOpcode::Sub => (Wrapping(l) - Wrapping(r)).0,
             ast::BinaryOpcode::Mul => (Wrapping(l) * Wrapping(r)).0,
+            ast::BinaryOpcode::Div if self.signed => (l as i32).wrapping_div(r as i32) as u32,
             ast::BinaryOpcode::Div => l / r,
+            ast::BinaryOpcode::Mod if self.signed => (l as i32).wrapping_rem(r as i32) as u32,
             ast::BinaryOpcode::Mod => l % r,
         };
 
@@ -69,7 +123,13 @@ impl fmt::Display for Binary {
         self.operation.fmt(f)?;
         f.write_char(' ')?;
         self.operands.1.fmt(f)?;
-        f.write_char(')')
+        f.write_char(')')?;
+
+        match (self.done_policy, self.done_reset) {
+            (ast::DonePolicy::All, ast::DoneReset::Sticky) => Ok(()),
+            (policy, ast::DoneReset::Sticky) => write!(f, ".done({})", policy),
+            (policy, reset) => write!(f, ".done({}, {})", policy, reset),
+        }
     }
 }
 
@@ -92,6 +152,7 @@ impl Expr for Unary {
         self.data.prev = match self.operation {
             ast::UnaryOpcode::Inv => !operand,
             ast::UnaryOpcode::Neg => (Wrapping(!operand) + Wrapping(1)).0,
+            ast::UnaryOpcode::Not => (operand == 0) as u32,
         };
 
         self.data.prev
@@ -0,0 +1,60 @@
+use crate::transform::CrateRng;
+use crate::model::{Expr, ExprData};
+use super::width_mask;
+
+use std::fmt;
+
+/// A bitfield record packed into a single value
+///
+/// The first field occupies the most significant bits.  Done is set when every field is done on
+/// the same next.
+///
+/// A field value that does not fit in the width of the field is silently truncated.
+#[derive(Clone)]
+pub struct Packed {
+    data: ExprData,
+    fields: Vec<(String, u32, Box<dyn Expr>)>,
+}
+
+impl Packed {
+    /// `fields` are (name, width, expression) in most to least significant order
+    pub fn new(fields: Vec<(String, u32, Box<dyn Expr>)>) -> Packed {
+        Packed {
+            data: Default::default(),
+            fields,
+        }
+    }
+}
+
+impl Expr for Packed {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let mut packed: u64 = 0;
+        let mut done = true;
+
+        for (_, width, expr) in self.fields.iter_mut() {
+            let value = expr.next(rng) & width_mask(*width);
+
+            packed = (packed << *width) | u64::from(value);
+            done &= expr.done();
+        }
+
+        self.data.prev = packed as u32;
+        self.data.done = done;
+
+        self.data.prev
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Packed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Packed {{")?;
+        for (name, width, expr) in &self.fields {
+            write!(f, "{}: {} = {}, ", name, width, expr)?;
+        }
+        write!(f, "}}")
+    }
+}
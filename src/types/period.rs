@@ -0,0 +1,216 @@
+use crate::transform::CrateRng;
+use crate::model::{Expr, ExprData};
+
+use std::fmt;
+
+/// Panics if the value of the `count` sub-expression is 0
+fn check_count(value: u32, count: &dyn Expr, expr: &dyn fmt::Display) {
+    if value == 0 {
+        panic!(
+            "the count sub-expression `{}` returned 0 in the expression `{}`",
+            count, expr
+        );
+    }
+}
+
+/// Repeats each value of `expr` `count` times
+///
+/// `count` is evaluated once per value of `expr`.  Done is set on the last repetition of a value
+/// for which `expr` is done.
+#[derive(Clone)]
+pub struct Repeat {
+    data: ExprData,
+    expr: Box<dyn Expr>,
+    count: Box<dyn Expr>,
+    remaining: u32,
+}
+
+impl Repeat {
+    pub fn new(expr: Box<dyn Expr>, count: Box<dyn Expr>) -> Repeat {
+        Repeat {
+            data: Default::default(),
+            expr,
+            count,
+            remaining: 0,
+        }
+    }
+}
+
+impl Expr for Repeat {
+    /// # Panics
+    ///
+    /// * If `count` returns 0
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        if self.remaining == 0 {
+            self.remaining = self.count.next(rng);
+            check_count(self.remaining, &*self.count, self);
+            self.data.prev = self.expr.next(rng);
+        }
+
+        self.remaining -= 1;
+        self.data.done = self.remaining == 0 && self.expr.done();
+
+        self.data.prev
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Repeat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Repeat({}, {})", self.expr, self.count)
+    }
+}
+
+/// Ends each period after `count` values of `expr`
+///
+/// `count` is evaluated once per period.  The doneness of `expr` is ignored.
+#[derive(Clone)]
+pub struct Take {
+    data: ExprData,
+    expr: Box<dyn Expr>,
+    count: Box<dyn Expr>,
+    remaining: u32,
+}
+
+impl Take {
+    pub fn new(expr: Box<dyn Expr>, count: Box<dyn Expr>) -> Take {
+        Take {
+            data: Default::default(),
+            expr,
+            count,
+            remaining: 0,
+        }
+    }
+}
+
+impl Expr for Take {
+    /// # Panics
+    ///
+    /// * If `count` returns 0
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        if self.remaining == 0 {
+            self.remaining = self.count.next(rng);
+            check_count(self.remaining, &*self.count, self);
+        }
+
+        self.data.prev = self.expr.next(rng);
+        self.remaining -= 1;
+        self.data.done = self.remaining == 0;
+
+        self.data.prev
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Take {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Take({}, {})", self.expr, self.count)
+    }
+}
+
+/// Discards the first `count` values of each period of `expr`
+///
+/// `count` is evaluated once per period.  Discarded values are drawn without regard to their
+/// doneness.  Done is set when `expr` is done.
+#[derive(Clone)]
+pub struct Skip {
+    data: ExprData,
+    expr: Box<dyn Expr>,
+    count: Box<dyn Expr>,
+    start: bool,
+}
+
+impl Skip {
+    pub fn new(expr: Box<dyn Expr>, count: Box<dyn Expr>) -> Skip {
+        Skip {
+            data: Default::default(),
+            expr,
+            count,
+            start: true,
+        }
+    }
+}
+
+impl Expr for Skip {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        if self.start {
+            for _ in 0..self.count.next(rng) {
+                self.expr.next(rng);
+            }
+        }
+
+        self.data.prev = self.expr.next(rng);
+        self.data.done = self.expr.done();
+        self.start = self.data.done;
+
+        self.data.prev
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Skip {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Skip({}, {})", self.expr, self.count)
+    }
+}
+
+/// Ends each period after `expr` has been done `count` times
+///
+/// `count` is evaluated once per period.
+#[derive(Clone)]
+pub struct Cycle {
+    data: ExprData,
+    expr: Box<dyn Expr>,
+    count: Box<dyn Expr>,
+    remaining: u32,
+}
+
+impl Cycle {
+    pub fn new(expr: Box<dyn Expr>, count: Box<dyn Expr>) -> Cycle {
+        Cycle {
+            data: Default::default(),
+            expr,
+            count,
+            remaining: 0,
+        }
+    }
+}
+
+impl Expr for Cycle {
+    /// # Panics
+    ///
+    /// * If `count` returns 0
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        if self.remaining == 0 {
+            self.remaining = self.count.next(rng);
+            check_count(self.remaining, &*self.count, self);
+        }
+
+        self.data.prev = self.expr.next(rng);
+        if self.expr.done() {
+            self.remaining -= 1;
+        }
+        self.data.done = self.remaining == 0;
+
+        self.data.prev
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Cycle({}, {})", self.expr, self.count)
+    }
+}
@@ -0,0 +1,46 @@
+use crate::transform::CrateRng;
+use crate::model::{Expr, ExprData};
+
+use std::cell::Cell;
+use std::fmt;
+use std::rc::Rc;
+
+/// Yields a value supplied by an enclosing expression
+///
+/// Used for `_` in `Where` predicates.  The enclosing expression stores the candidate value in the
+/// shared cell before evaluating the predicate.
+#[derive(Clone)]
+pub struct Placeholder {
+    data: ExprData,
+    name: String,
+    value: Rc<Cell<u32>>,
+}
+
+impl Placeholder {
+    pub fn new(name: &str, value: Rc<Cell<u32>>) -> Placeholder {
+        Placeholder {
+            data: Default::default(),
+            name: name.into(),
+            value,
+        }
+    }
+}
+
+impl Expr for Placeholder {
+    fn next(&mut self, _rng: &mut CrateRng) -> u32 {
+        self.data.prev = self.value.get();
+        self.data.done = true;
+
+        self.data.prev
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Placeholder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
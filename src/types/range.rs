@@ -2,15 +2,21 @@ use std::fmt;
 use std::u32;
 use rand::distributions::Distribution;
 use rand::distributions::uniform::Uniform;
+use rand::Rng;
 
 use crate::transform::CrateRng;
 use crate::model::{Expr, ExprData};
+use crate::types::{write_signed, Intervals};
+
+/// Flips the sign bit so that signed order matches unsigned order
+pub const SIGN_BIT: u32 = 0x8000_0000;
 
 #[derive(Clone)]
 pub struct Range {
     data: ExprData,
     l: u32,
     r: u32,
+    signed: bool,
     range: Uniform<u32>,
 }
 
@@ -22,6 +28,23 @@ impl Range {
             data: Default::default(),
             l,
             r,
+            signed: false,
+            range: Uniform::new_inclusive(limits.0, limits.1),
+        }
+    }
+
+    /// Creates a range whose limits are two's complement signed values
+    ///
+    /// E.g. `Range::new_signed(-5i32 as u32, 5)` returns values in [-5, 5].
+    pub fn new_signed(l: u32, r: u32) -> Range {
+        let (ol, or) = (l ^ SIGN_BIT, r ^ SIGN_BIT);
+        let limits = if or > ol { (ol, or) } else { (or, ol) };
+
+        Range {
+            data: Default::default(),
+            l,
+            r,
+            signed: true,
             range: Uniform::new_inclusive(limits.0, limits.1),
         }
     }
@@ -29,7 +52,9 @@ impl Range {
 
 impl Expr for Range {
     fn next(&mut self, rng: &mut CrateRng) -> u32 {
-        self.data.prev = self.range.sample(rng);
+        let offset = if self.signed { SIGN_BIT } else { 0 };
+
+        self.data.prev = self.range.sample(rng) ^ offset;
         self.data.done = true;
 
         self.data.prev
@@ -42,7 +67,174 @@ impl Expr for Range {
 
 impl fmt::Display for Range {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "[0x{:x}, 0x{:x}]", self.l, self.r)
+        if self.signed {
+            write!(f, "[")?;
+            write_signed(f, self.l)?;
+            write!(f, ", ")?;
+            write_signed(f, self.r)?;
+            write!(f, "]")
+        } else {
+            write!(f, "[0x{:x}, 0x{:x}]", self.l, self.r)
+        }
+    }
+}
+
+/// Returns a random value in the range [l, r] inclusive where the limits are evaluated on every draw
+///
+/// Used when a limit depends on the host.  E.g. `[0, depth]` where `depth` is an input or
+/// `[1, mem_free()]`.  Done is set when both limits are done.
+#[derive(Clone)]
+pub struct DynamicRange {
+    data: ExprData,
+    l: Box<dyn Expr>,
+    r: Box<dyn Expr>,
+    signed: bool,
+}
+
+impl DynamicRange {
+    pub fn new(l: Box<dyn Expr>, r: Box<dyn Expr>, signed: bool) -> DynamicRange {
+        DynamicRange {
+            data: Default::default(),
+            l,
+            r,
+            signed,
+        }
+    }
+}
+
+impl Expr for DynamicRange {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let offset = if self.signed { SIGN_BIT } else { 0 };
+        let l = self.l.next(rng) ^ offset;
+        let r = self.r.next(rng) ^ offset;
+        let limits = if r > l { (l, r) } else { (r, l) };
+
+        let value = rng.gen_range(u64::from(limits.0), u64::from(limits.1) + 1) as u32;
+        self.data.prev = value ^ offset;
+        self.data.done = self.l.done() && self.r.done();
+
+        self.data.prev
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for DynamicRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}, {}]", self.l, self.r)
+    }
+}
+
+/// Returns a random value in the range [l, r] inclusive excluding a set of values
+///
+/// Samples exactly.  A single draw selects the Nth allowed value instead of retrying on excluded
+/// values.
+///
+/// Excluded intervals are (low, high) pairs in the order of the range.  I.e. signed order for
+/// signed ranges.
+#[derive(Clone)]
+pub struct RangeExcept {
+    data: ExprData,
+    l: u32,
+    r: u32,
+    excluded: Vec<(u32, u32)>,
+    signed: bool,
+    /// Allowed values with the sign bit flipped if signed
+    allowed: Intervals,
+    range: Uniform<u64>,
+}
+
+impl RangeExcept {
+    /// # Panics
+    ///
+    /// * If `excluded` excludes every value in [l, r]
+    pub fn new(l: u32, r: u32, excluded: Vec<(u32, u32)>) -> RangeExcept {
+        RangeExcept::with_sign(l, r, excluded, false)
+    }
+
+    /// Creates a range whose limits and excluded values are two's complement signed values
+    ///
+    /// # Panics
+    ///
+    /// * If `excluded` excludes every value in [l, r]
+    pub fn new_signed(l: u32, r: u32, excluded: Vec<(u32, u32)>) -> RangeExcept {
+        RangeExcept::with_sign(l, r, excluded, true)
+    }
+
+    fn with_sign(l: u32, r: u32, excluded: Vec<(u32, u32)>, signed: bool) -> RangeExcept {
+        let allowed = RangeExcept::allowed_values(l, r, &excluded, signed);
+
+        RangeExcept {
+            data: Default::default(),
+            l,
+            r,
+            excluded,
+            signed,
+            range: Uniform::new(0, allowed.len()),
+            allowed,
+        }
+    }
+
+    /// Returns the values in [l, r] inclusive that are not in `excluded`
+    ///
+    /// If `signed`, the values are returned with the sign bit flipped.
+    pub fn allowed_values(l: u32, r: u32, excluded: &[(u32, u32)], signed: bool) -> Intervals {
+        let offset = if signed { SIGN_BIT } else { 0 };
+
+        let mut allowed = Intervals::new(l ^ offset, r ^ offset);
+        for &(l, r) in excluded {
+            allowed.remove(l ^ offset, r ^ offset);
+        }
+
+        allowed
+    }
+}
+
+impl Expr for RangeExcept {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let offset = if self.signed { SIGN_BIT } else { 0 };
+
+        self.data.prev = self.allowed.nth(self.range.sample(rng)) ^ offset;
+        self.data.done = true;
+
+        self.data.prev
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for RangeExcept {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let write_value = |f: &mut fmt::Formatter, value: u32| {
+            if self.signed {
+                write_signed(f, value)
+            } else {
+                write!(f, "0x{:x}", value)
+            }
+        };
+
+        write!(f, "[")?;
+        write_value(f, self.l)?;
+        write!(f, ", ")?;
+        write_value(f, self.r)?;
+        write!(f, "] except {{")?;
+        for &(l, r) in &self.excluded {
+            if l == r {
+                write_value(f, l)?;
+            } else {
+                write!(f, "[")?;
+                write_value(f, l)?;
+                write!(f, ", ")?;
+                write_value(f, r)?;
+                write!(f, "]")?;
+            }
+            write!(f, ", ")?;
+        }
+        write!(f, "}}")
     }
 }
 
@@ -76,6 +268,17 @@ mod tests {
             assert!(num_ones > 450 && num_ones < 550);
         }
 
+        #[test]
+        fn signed() {
+            let mut range = Range::new_signed(-2i32 as u32, 2);
+
+            let mut rng = Seed::from_u32(0).to_rng();
+            for _ in 0..100 {
+                let value = range.next(&mut rng) as i32;
+                assert!((-2..=2).contains(&value));
+            }
+        }
+
         #[test]
         fn max_max() {
             use std::collections::HashMap;
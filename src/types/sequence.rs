@@ -1,64 +1,96 @@
-use crate::types::Value;
 use crate::transform::CrateRng;
 use crate::model::{Expr, ExprData};
+use crate::types::SIGN_BIT;
 
 use std::num::Wrapping;
 use std::fmt;
 
+/// How a `Sequence` determines the end of a period
+#[derive(Clone)]
+pub enum SequenceEnd {
+    /// The period ends on or after reaching `last`
+    Last(Box<dyn Expr>),
+    /// The period ends after `count` values.  Values wrap modulo `wrap` if given.
+    Count(Box<dyn Expr>, Option<Box<dyn Expr>>),
+}
+
 #[derive(Clone)]
 pub struct Sequence {
     data: ExprData,
     next: Wrapping<u32>,
     first: Box<dyn Expr>,
-    last: Box<dyn Expr>,
+    end: SequenceEnd,
     increment: Box<dyn Expr>,
     compare: bool,
+    /// Number of values left in the current period when counting
+    remaining: u32,
+    /// Set until the parameters of the first period are drawn on the first next
+    pending: bool,
+    /// Whether `last` is compared as a two's complement signed value
+    signed: bool,
 }
 
 impl Sequence {
     /// # Panics
     ///
-    /// * If `args.len()` < 1 OR > 3
     /// * If increment is 0
-    pub fn new(mut args: Vec<Box<dyn Expr>>, rng: &mut CrateRng) -> Sequence {
-        let len = args.len();
-        let mut drain = args.drain(..);
-        let (first, last, increment): (Box<dyn Expr>, Box<dyn Expr>, Box<dyn Expr>) = match len {
-            1 => (
-                Box::new(Value::new(0)),
-                drain.next().unwrap(),
-                Box::new(Value::new(1)),
-            ),
-            2 => (
-                drain.next().unwrap(),
-                drain.next().unwrap(),
-                Box::new(Value::new(1)),
-            ),
-            3 => (
-                drain.next().unwrap(),
-                drain.next().unwrap(),
-                drain.next().unwrap(),
-            ),
-            _ => panic!("Expected 1 to 3 arguments.  Got {}", len),
-        };
+    /// * If count or wrap is 0
+    pub fn new(
+        first: Box<dyn Expr>,
+        end: SequenceEnd,
+        increment: Box<dyn Expr>,
+        rng: &mut CrateRng,
+    ) -> Sequence {
+        Sequence::deferred(first, end, increment, false).init(rng)
+    }
 
-        let mut sequence = Sequence {
+    /// Creates a sequence that compares values as two's complement signed values
+    ///
+    /// # Panics
+    ///
+    /// * If increment is 0
+    /// * If count or wrap is 0
+    pub fn new_signed(
+        first: Box<dyn Expr>,
+        end: SequenceEnd,
+        increment: Box<dyn Expr>,
+        rng: &mut CrateRng,
+    ) -> Sequence {
+        Sequence::deferred(first, end, increment, true).init(rng)
+    }
+
+    /// Creates a sequence that draws the parameters of its first period on the first `next`
+    ///
+    /// Used when a parameter is host-driven so that values set after transform are seen.
+    pub fn deferred(
+        first: Box<dyn Expr>,
+        end: SequenceEnd,
+        increment: Box<dyn Expr>,
+        signed: bool,
+    ) -> Sequence {
+        Sequence {
             data: Default::default(),
             next: Wrapping(0),
             first,
-            last,
+            end,
             increment,
             compare: false,
-        };
+            remaining: 0,
+            pending: true,
+            signed,
+        }
+    }
 
-        sequence.init_params(rng);
+    fn init(mut self, rng: &mut CrateRng) -> Sequence {
+        self.pending = false;
+        self.init_params(rng);
 
-        sequence
+        self
     }
 
     fn init_params(&mut self, rng: &mut CrateRng) {
         self.init_next(rng);
-        self.init_last(rng);
+        self.init_end(rng);
         self.init_increment(rng);
 
         self.compare = self.compare();
@@ -75,24 +107,78 @@ impl Sequence {
         }
     }
 
-    fn init_last(&mut self, rng: &mut CrateRng) {
-        self.last.next(rng);
+    fn init_end(&mut self, rng: &mut CrateRng) {
+        let (count, wrap) = match self.end {
+            SequenceEnd::Last(ref mut last) => {
+                last.next(rng);
+                return;
+            }
+            SequenceEnd::Count(ref mut count, ref mut wrap) => {
+                (count.next(rng), wrap.as_mut().map(|wrap| wrap.next(rng)))
+            }
+        };
+
+        if let SequenceEnd::Count(ref count_expr, ref wrap_expr) = self.end {
+            if count == 0 {
+                panic!(
+                    "the count sub-expression `{}` returned 0 in the expression `{}`",
+                    count_expr, self
+                );
+            }
+
+            if let (Some(0), Some(wrap_expr)) = (wrap, wrap_expr) {
+                panic!(
+                    "the wrap sub-expression `{}` returned 0 in the expression `{}`",
+                    wrap_expr, self
+                );
+            }
+        }
+
+        self.remaining = count;
+        if let Some(wrap) = wrap {
+            self.next = Wrapping(self.next.0 % wrap);
+        }
     }
 
     fn init_next(&mut self, rng: &mut CrateRng) {
         self.next = Wrapping(self.first.next(rng));
     }
 
+    fn last(&self) -> u32 {
+        match self.end {
+            SequenceEnd::Last(ref last) => last.prev(),
+            SequenceEnd::Count(..) => 0,
+        }
+    }
+
     fn compare(&self) -> bool {
-        if self.next.0 == self.last.prev() {
+        let offset = if self.signed { SIGN_BIT } else { 0 };
+
+        if self.next.0 == self.last() {
             self.compare
         } else {
-            self.next.0 < self.last.prev()
+            self.next.0 ^ offset < self.last() ^ offset
         }
     }
 
     fn is_last(&self) -> bool {
-        self.next.0 == self.last.prev()
+        self.next.0 == self.last()
+    }
+
+    /// Advances to the next value
+    ///
+    /// When wrapping, the increment is interpreted as a signed 32-bit value.
+    fn advance(&mut self) {
+        let increment = self.increment.prev();
+
+        match self.end {
+            SequenceEnd::Count(_, Some(ref wrap)) => {
+                let size = i64::from(wrap.prev());
+                let next = i64::from(self.next.0) + i64::from(increment as i32);
+                self.next = Wrapping(next.rem_euclid(size) as u32);
+            }
+            _ => self.next += Wrapping(increment),
+        }
     }
 
     fn past_last(&self) -> bool {
@@ -109,14 +195,27 @@ impl Expr for Sequence {
     /// # Panics
     ///
     /// * If increment returns 0
+    /// * If count or wrap returns 0
     fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        if self.pending {
+            self.pending = false;
+            self.init_params(rng);
+        }
+
         self.data.prev = self.next.0;
         self.data.done = false;
 
-        if self.is_last() {
+        if let SequenceEnd::Count(..) = self.end {
+            self.remaining -= 1;
+            if self.remaining == 0 {
+                self.done(rng);
+            } else {
+                self.advance();
+            }
+        } else if self.is_last() {
             self.done(rng);
         } else {
-            self.next += Wrapping(self.increment.prev());
+            self.advance();
 
             if self.past_last() {
                 self.done(rng);
@@ -133,10 +232,23 @@ impl Expr for Sequence {
 
 impl fmt::Display for Sequence {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "Sequence({}, {}, {})",
-            self.first, self.last, self.increment
-        )
+        match self.end {
+            SequenceEnd::Last(ref last) => write!(
+                f,
+                "Sequence({}, {}, {})",
+                self.first, last, self.increment
+            ),
+            SequenceEnd::Count(ref count, ref wrap) => {
+                write!(
+                    f,
+                    "Sequence(first = {}, count = {}, increment = {}",
+                    self.first, count, self.increment
+                )?;
+                if let Some(ref wrap) = *wrap {
+                    write!(f, ", wrap = {}", wrap)?;
+                }
+                write!(f, ")")
+            }
+        }
     }
 }
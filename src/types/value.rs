@@ -3,9 +3,23 @@ use crate::model::{Expr, ExprData};
 
 use std::fmt;
 
+/// Writes `value` as a two's complement signed hexadecimal number
+///
+/// E.g. `0xfffffffb` is written as `-0x5`.
+pub fn write_signed(f: &mut fmt::Formatter, value: u32) -> fmt::Result {
+    let value = value as i32;
+
+    if value < 0 {
+        write!(f, "-0x{:x}", -i64::from(value))
+    } else {
+        write!(f, "0x{:x}", value)
+    }
+}
+
 #[derive(Clone)]
 pub struct Value {
     data: ExprData,
+    signed: bool,
 }
 
 impl Value {
@@ -15,6 +29,15 @@ impl Value {
                 prev: value,
                 done: false,
             },
+            signed: false,
+        }
+    }
+
+    /// Creates a value that displays as a signed number
+    pub fn new_signed(value: u32) -> Value {
+        Value {
+            signed: true,
+            ..Value::new(value)
         }
     }
 }
@@ -33,6 +56,10 @@ impl Expr for Value {
 
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "0x{:x}", self.data.prev)
+        if self.signed {
+            write_signed(f, self.data.prev)
+        } else {
+            write!(f, "0x{:x}", self.data.prev)
+        }
     }
 }
@@ -17,6 +17,15 @@ pub struct Prev {
     data: ExprData,
 }
 
+/// Returns the nth most recent value of a variable
+#[derive(Clone)]
+pub struct PrevN {
+    variable: VariableWeak,
+    variable_name: String,
+    n: u32,
+    data: ExprData,
+}
+
 impl Next {
     pub fn new(variable_name: &str, variable: VariableWeak) -> Next {
         Next {
@@ -84,3 +93,38 @@ impl fmt::Display for Prev {
         write!(f, "{}.prev", self.variable_name)
     }
 }
+
+impl PrevN {
+    pub fn new(variable_name: &str, variable: VariableWeak, n: u32) -> PrevN {
+        PrevN {
+            variable,
+            variable_name: variable_name.into(),
+            n,
+            data: Default::default(),
+        }
+    }
+}
+
+impl Expr for PrevN {
+    /// # Errors
+    ///
+    /// If Weak pointer cannot be upgraded, next() will return previous value.
+    fn next(&mut self, _rng: &mut CrateRng) -> u32 {
+        if let Some(variable) = self.variable.upgrade() {
+            self.data.prev = variable.borrow().history(self.n as usize);
+            self.data.done = variable.borrow().done();
+        }
+
+        self.data.prev
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for PrevN {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.prev({})", self.variable_name, self.n)
+    }
+}
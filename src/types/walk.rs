@@ -0,0 +1,242 @@
+use crate::transform::CrateRng;
+use crate::model::{Expr, ExprData};
+use crate::types::write_signed;
+
+use rand::Rng;
+use std::fmt;
+
+/// Handling of steps that leave the [lo, hi] bounds of a `Walk`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Boundary {
+    /// Continue from the opposite bound
+    Wrap,
+    /// Bounce back off the bound
+    Reflect,
+}
+
+impl Boundary {
+    /// Maps `offset` from `lo` into [0, len)
+    fn apply(self, offset: i64, len: i64) -> i64 {
+        match self {
+            Boundary::Wrap => offset.rem_euclid(len),
+            Boundary::Reflect => {
+                if len == 1 {
+                    return 0;
+                }
+
+                let period = 2 * (len - 1);
+                let offset = offset.rem_euclid(period);
+                if offset < len {
+                    offset
+                } else {
+                    period - offset
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Boundary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Boundary::Wrap => write!(f, "wrap"),
+            Boundary::Reflect => write!(f, "reflect"),
+        }
+    }
+}
+
+/// A random walk within [lo, hi] inclusive
+///
+/// Yields `start` first.  Each following value adds `step` to the previous value.  `step` is
+/// interpreted as a signed 32-bit value.  E.g. `[0, 8] - 4` steps by -4 to 4.
+///
+/// Done is set on every value.
+#[derive(Clone)]
+pub struct Walk {
+    data: ExprData,
+    start: Box<dyn Expr>,
+    step: Box<dyn Expr>,
+    lo: u32,
+    hi: u32,
+    boundary: Boundary,
+    signed: bool,
+    started: bool,
+}
+
+/// Sign or zero extends `value`
+fn extend(value: u32, signed: bool) -> i64 {
+    if signed {
+        i64::from(value as i32)
+    } else {
+        i64::from(value)
+    }
+}
+
+impl Walk {
+    pub fn new(
+        start: Box<dyn Expr>,
+        step: Box<dyn Expr>,
+        lo: u32,
+        hi: u32,
+        boundary: Boundary,
+    ) -> Walk {
+        Walk::with_sign(start, step, lo, hi, boundary, false)
+    }
+
+    /// Creates a walk whose start and limits are two's complement signed values
+    pub fn new_signed(
+        start: Box<dyn Expr>,
+        step: Box<dyn Expr>,
+        lo: u32,
+        hi: u32,
+        boundary: Boundary,
+    ) -> Walk {
+        Walk::with_sign(start, step, lo, hi, boundary, true)
+    }
+
+    fn with_sign(
+        start: Box<dyn Expr>,
+        step: Box<dyn Expr>,
+        lo: u32,
+        hi: u32,
+        boundary: Boundary,
+        signed: bool,
+    ) -> Walk {
+        let (lo, hi) = if extend(hi, signed) > extend(lo, signed) { (lo, hi) } else { (hi, lo) };
+
+        Walk {
+            data: Default::default(),
+            start,
+            step,
+            lo,
+            hi,
+            boundary,
+            signed,
+            started: false,
+        }
+    }
+
+    fn bound(&self, value: i64) -> u32 {
+        let lo = extend(self.lo, self.signed);
+        let len = extend(self.hi, self.signed) - lo + 1;
+        let offset = self.boundary.apply(value - lo, len);
+
+        (lo + offset) as u32
+    }
+}
+
+impl Expr for Walk {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let value = if self.started {
+            extend(self.data.prev, self.signed) + i64::from(self.step.next(rng) as i32)
+        } else {
+            self.started = true;
+            extend(self.start.next(rng), self.signed)
+        };
+
+        self.data.prev = self.bound(value);
+        self.data.done = true;
+
+        self.data.prev
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Walk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.signed {
+            write!(f, "Walk({}, {}, ", self.start, self.step)?;
+            write_signed(f, self.lo)?;
+            write!(f, ", ")?;
+            write_signed(f, self.hi)?;
+            write!(f, ", {})", self.boundary)
+        } else {
+            write!(
+                f,
+                "Walk({}, {}, 0x{:x}, 0x{:x}, {})",
+                self.start, self.step, self.lo, self.hi, self.boundary
+            )
+        }
+    }
+}
+
+/// A random value within `radius` of `base`
+///
+/// The result is clamped to [0, 0xffffffff], or [-0x80000000, 0x7fffffff] if signed.  Done is set
+/// on every value.
+#[derive(Clone)]
+pub struct Near {
+    data: ExprData,
+    base: Box<dyn Expr>,
+    radius: Box<dyn Expr>,
+    signed: bool,
+}
+
+impl Near {
+    pub fn new(base: Box<dyn Expr>, radius: Box<dyn Expr>) -> Near {
+        Near {
+            data: Default::default(),
+            base,
+            radius,
+            signed: false,
+        }
+    }
+
+    /// Creates a Near whose base is a two's complement signed value
+    pub fn new_signed(base: Box<dyn Expr>, radius: Box<dyn Expr>) -> Near {
+        Near {
+            signed: true,
+            ..Near::new(base, radius)
+        }
+    }
+}
+
+impl Expr for Near {
+    fn next(&mut self, rng: &mut CrateRng) -> u32 {
+        let base = extend(self.base.next(rng), self.signed);
+        let radius = i64::from(self.radius.next(rng));
+        let value = rng.gen_range(base - radius, base + radius + 1);
+
+        self.data.prev = if self.signed {
+            value.max(i64::from(i32::MIN)).min(i64::from(i32::MAX)) as u32
+        } else {
+            value.max(0).min(i64::from(u32::MAX)) as u32
+        };
+        self.data.done = true;
+
+        self.data.prev
+    }
+
+    fn data(&self) -> &ExprData {
+        &self.data
+    }
+}
+
+impl fmt::Display for Near {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Near({}, {})", self.base, self.radius)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap() {
+        let offsets: Vec<i64> = (-5..6).map(|offset| Boundary::Wrap.apply(offset, 4)).collect();
+
+        assert_eq!(offsets, [3, 0, 1, 2, 3, 0, 1, 2, 3, 0, 1]);
+    }
+
+    #[test]
+    fn reflect() {
+        let offsets: Vec<i64> = (-5..6).map(|offset| Boundary::Reflect.apply(offset, 4)).collect();
+
+        assert_eq!(offsets, [1, 2, 3, 2, 1, 0, 1, 2, 3, 2, 1]);
+        assert_eq!(Boundary::Reflect.apply(7, 1), 0);
+    }
+}
@@ -1,9 +1,9 @@
 use crate::transform::CrateRng;
 use crate::model::{Expr, ExprData};
 
+use rand::Rng;
 use rand::distributions::Distribution;
 use rand::distributions::uniform::Uniform;
-use rand::seq::SliceRandom;
 use std::fmt;
 
 #[derive(Clone)]
@@ -11,40 +11,47 @@ pub struct WeightedWithReplacement {
     data: ExprData,
     weights: Vec<u32>,
     children: Vec<Box<dyn Expr>>,
-    range: Uniform<usize>,
-    pool: Vec<usize>,
-    pool_index: Option<usize>,
+    /// Running sum of `weights`.  Searched to map a sample to a child index.
+    cumulative: Vec<u64>,
+    range: Uniform<u64>,
+    child_index: Option<usize>,
 }
 
 impl WeightedWithReplacement {
+    /// # Panics
+    ///
+    /// * If the sum of `weights` is 0
     pub fn new(weights: Vec<u32>, children: Vec<Box<dyn Expr>>) -> WeightedWithReplacement {
-        let pool = populate_pool(&weights);
+        let cumulative = cumulative_weights(&weights);
+        let total = *cumulative.last().unwrap_or(&0);
 
         WeightedWithReplacement {
             data: Default::default(),
             weights,
             children,
-            range: Uniform::new(0, pool.len()),
-            pool,
-            pool_index: None,
+            cumulative,
+            range: Uniform::new(0, total),
+            child_index: None,
         }
     }
 }
 
 impl Expr for WeightedWithReplacement {
     fn next(&mut self, rng: &mut CrateRng) -> u32 {
-        let pool_index = match self.pool_index {
-            Some(pool_index) => pool_index,
-            None => self.range.sample(rng),
+        let child_index = match self.child_index {
+            Some(child_index) => child_index,
+            None => {
+                let sample = self.range.sample(rng);
+                self.cumulative.partition_point(|&sum| sum <= sample)
+            }
         };
-        let child_index = self.pool[pool_index];
 
         self.data.prev = self.children[child_index].next(rng);
         self.data.done = self.children[child_index].done();
-        self.pool_index = if self.data.done {
+        self.child_index = if self.data.done {
             None
         } else {
-            Some(pool_index)
+            Some(child_index)
         };
 
         self.data.prev
@@ -70,41 +77,46 @@ pub struct WeightedWithoutReplacement {
     data: ExprData,
     weights: Vec<u32>,
     children: Vec<Box<dyn Expr>>,
-    pool: Vec<usize>,
-    pool_index: usize,
+    /// Number of times each child may still be selected in the current period
+    remaining: Counts,
+    child_index: Option<usize>,
 }
 
 impl WeightedWithoutReplacement {
-    pub fn new(
-        weights: Vec<u32>,
-        children: Vec<Box<dyn Expr>>,
-        rng: &mut CrateRng,
-    ) -> WeightedWithoutReplacement {
-        let mut pool = populate_pool(&weights);
-        pool.shuffle(rng);
-
+    pub fn new(weights: Vec<u32>, children: Vec<Box<dyn Expr>>) -> WeightedWithoutReplacement {
         WeightedWithoutReplacement {
             data: Default::default(),
+            remaining: Counts::new(&weights),
             weights,
             children,
-            pool,
-            pool_index: 0,
+            child_index: None,
         }
     }
 }
 
 impl Expr for WeightedWithoutReplacement {
+    /// # Panics
+    ///
+    /// * If the sum of the weights is 0
     fn next(&mut self, rng: &mut CrateRng) -> u32 {
-        let child_index = self.pool[self.pool_index];
+        let child_index = match self.child_index {
+            Some(child_index) => child_index,
+            None => {
+                let sample = rng.gen_range(0, self.remaining.total());
+                self.remaining.find(sample)
+            }
+        };
+
         self.data.prev = self.children[child_index].next(rng);
 
         self.data.done = false;
+        self.child_index = Some(child_index);
         if self.children[child_index].done() {
-            self.pool_index += 1;
-            if self.pool_index == self.pool.len() {
-                self.pool_index = 0;
+            self.child_index = None;
+            self.remaining.decrement(child_index);
+            if self.remaining.total() == 0 {
                 self.data.done = true;
-                self.pool.shuffle(rng);
+                self.remaining = Counts::new(&self.weights);
             }
         }
 
@@ -126,18 +138,78 @@ impl fmt::Display for WeightedWithoutReplacement {
     }
 }
 
-/// Converts weights into a pool of indexes
+/// Converts weights into a running sum of weights
+fn cumulative_weights(weights: &[u32]) -> Vec<u64> {
+    weights
+        .iter()
+        .scan(0u64, |sum, weight| {
+            *sum += u64::from(*weight);
+            Some(*sum)
+        })
+        .collect()
+}
+
+/// Per-child counts stored in a Fenwick (binary indexed) tree
 ///
-/// The index of each weight is added to the pool <weight> times.
-fn populate_pool(weights: &[u32]) -> Vec<usize> {
-    let mut pool: Vec<usize> = Vec::new();
-    for (i, weight) in weights.iter().enumerate() {
-        for _ in 0..*weight {
-            pool.push(i);
+/// Selecting and decrementing a child are both O(log n) in the number of children.  Memory is
+/// proportional to the number of children instead of the sum of the weights.
+#[derive(Clone)]
+struct Counts {
+    /// 1-based Fenwick tree.  `tree[0]` is unused.
+    tree: Vec<u64>,
+    total: u64,
+}
+
+impl Counts {
+    fn new(weights: &[u32]) -> Counts {
+        let mut tree = vec![0u64; weights.len() + 1];
+        let mut total = 0;
+
+        for (i, weight) in weights.iter().enumerate() {
+            let index = i + 1;
+            tree[index] += u64::from(*weight);
+            total += u64::from(*weight);
+
+            let parent = index + (index & index.wrapping_neg());
+            if parent < tree.len() {
+                tree[parent] += tree[index];
+            }
+        }
+
+        Counts { tree, total }
+    }
+
+    fn total(&self) -> u64 {
+        self.total
+    }
+
+    fn decrement(&mut self, child_index: usize) {
+        let mut index = child_index + 1;
+        while index < self.tree.len() {
+            self.tree[index] -= 1;
+            index += index & index.wrapping_neg();
         }
+
+        self.total -= 1;
     }
 
-    pool
+    /// Returns the index of the child that owns `sample` where `sample` is in [0, total)
+    fn find(&self, mut sample: u64) -> usize {
+        let len = self.tree.len() - 1;
+        let mut index = 0;
+        let mut step = len.next_power_of_two();
+
+        while step > 0 {
+            let next = index + step;
+            if next <= len && self.tree[next] <= sample {
+                index = next;
+                sample -= self.tree[next];
+            }
+            step >>= 1;
+        }
+
+        index
+    }
 }
 
 #[cfg(test)]
@@ -145,7 +217,23 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_populate_pool() {
-        assert_eq!(populate_pool(&[3, 1, 2]), [0, 0, 0, 1, 2, 2]);
+    fn test_cumulative_weights() {
+        assert_eq!(cumulative_weights(&[3, 1, 2]), [3, 4, 6]);
+    }
+
+    #[test]
+    fn test_counts() {
+        let mut counts = Counts::new(&[3, 1, 0, 2]);
+        assert_eq!(counts.total(), 6);
+
+        let found: Vec<usize> = (0..6).map(|sample| counts.find(sample)).collect();
+        assert_eq!(found, [0, 0, 0, 1, 3, 3]);
+
+        counts.decrement(1);
+        counts.decrement(0);
+        assert_eq!(counts.total(), 4);
+
+        let found: Vec<usize> = (0..4).map(|sample| counts.find(sample)).collect();
+        assert_eq!(found, [0, 0, 3, 3]);
     }
 }
@@ -0,0 +1,104 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn no_overlap() {
+    let a = expr_to_var("Alloc(base = 0x1000, size = 0x1000, len = [1, 0x40], align = 0x10)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut regions: Vec<(u32, u32)> = Vec::new();
+    for _ in 0..32 {
+        let start = a.next();
+        let len = a.region_len();
+
+        assert_eq!(start % 0x10, 0);
+        assert!((1..=0x40).contains(&len));
+        assert!(start >= 0x1000 && start + len <= 0x2000);
+        for &(l, n) in &regions {
+            assert!(start + len <= l || l + n <= start);
+        }
+
+        regions.push((start, len));
+    }
+}
+
+#[test]
+fn release() {
+    let a = expr_to_var("Alloc(0, 0x30, 0x10, 0x10)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut starts: Vec<u32> = (0..3).map(|_| a.next()).collect();
+    starts.sort();
+    assert_eq!(starts, [0x0, 0x10, 0x20]);
+
+    assert!(a.release(0x10));
+    assert!(!a.release(0x10));
+    assert!(!a.release(0x18));
+
+    assert_eq!(a.next(), 0x10);
+}
+
+#[test]
+fn full() {
+    let a = expr_to_var("Alloc(0, 0x20, 0x10, 0x10)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let start = a.next();
+    a.next();
+    assert!(!a.exhausted());
+
+    a.next();
+    assert!(a.exhausted());
+    assert_eq!(a.region_len(), 0);
+
+    assert!(a.release(start));
+    assert_eq!(a.next(), start);
+    assert!(!a.exhausted());
+    assert_eq!(a.region_len(), 0x10);
+}
+
+#[test]
+fn len_exceeds_size() {
+    let a = expr_to_var("Alloc(0, 0x20, Pattern(0x40, 0x10))").unwrap();
+    let mut a = a.borrow_mut();
+
+    a.next();
+    assert!(a.exhausted());
+
+    a.next();
+    assert!(!a.exhausted());
+    assert_eq!(a.region_len(), 0x10);
+}
+
+#[test]
+#[should_panic(expected = "returned 0")]
+fn zero_len() {
+    let a = expr_to_var("Alloc(0, 0x20, Pattern(0))").unwrap();
+    let mut a = a.borrow_mut();
+
+    a.next();
+}
+
+#[test]
+fn transform_errors() {
+    assert!(expr_to_var("Alloc(0, 0, 1)").is_err());
+    assert!(expr_to_var("Alloc(0xffff_ffff, 2, 1)").is_err());
+    assert!(expr_to_var("Alloc(0, 0x10, 1, 0)").is_err());
+    assert!(expr_to_var("Alloc(0, 0x20, 0)").is_err());
+    assert!(expr_to_var("Alloc(0, 0x20, 0x21)").is_err());
+    assert!(expr_to_var("Alloc(0, 0x20, 0x20)").is_ok());
+    assert!(expr_to_var("Alloc(0xffff_ff00, 0x100, 1)").is_ok());
+}
+
+#[test]
+fn display() {
+    let model = rvs::parse(&Default::default(), "a = Alloc(0x100, 0x40, [1, 4], 4);").unwrap();
+
+    assert_eq!(model.to_string(), "a = Alloc(0x100, 0x40, [0x1, 0x4], 0x4);\n");
+}
+
+#[test]
+fn host_driven_arguments() {
+    assert!(rvs::parse(&Default::default(), "input d = 4; a = Alloc(0, d, 0x10);").is_err());
+    assert!(rvs::parse(&Default::default(), "input d = 4; a = Alloc(0, 0x20, 0x10, d);").is_err());
+}
@@ -0,0 +1,156 @@
+mod util;
+use crate::util::*;
+
+use std::collections::HashSet;
+
+#[test]
+fn walking_ones() {
+    let a = expr_to_var("WalkingOnes(4)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<(u32, bool)> = vec![(1, false), (2, false), (4, false), (8, true)]
+        .into_iter()
+        .cycle()
+        .take(8)
+        .collect();
+    let actual: Vec<(u32, bool)> = (0..8).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn walking_ones_full_width() {
+    let a = expr_to_var("WalkingOnes(32)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<u32> = (0..32).map(|_| a.next()).collect();
+
+    assert_eq!(actual[31], 0x8000_0000);
+    assert!(a.done());
+}
+
+#[test]
+fn walking_zeros() {
+    let a = expr_to_var("WalkingZeros(4)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<(u32, bool)> = vec![(0xe, false), (0xd, false), (0xb, false), (0x7, true)];
+    let actual: Vec<(u32, bool)> = (0..4).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn gray() {
+    let a = expr_to_var("Gray(3)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let values: Vec<u32> = (0..8).map(|_| a.next()).collect();
+    assert_eq!(values, [0, 1, 3, 2, 6, 7, 5, 4]);
+    assert!(a.done());
+
+    for pair in values.windows(2) {
+        assert_eq!((pair[0] ^ pair[1]).count_ones(), 1);
+    }
+}
+
+#[test]
+fn lfsr_maximal_length() {
+    let a = expr_to_var("Lfsr(16, 0xb400, 0xace1)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut values = HashSet::new();
+    loop {
+        values.insert(a.next());
+        if a.done() {
+            break;
+        }
+    }
+
+    assert_eq!(values.len(), 0xffff);
+    assert!(!values.contains(&0));
+    assert_eq!(a.next(), 0xace1);
+}
+
+#[test]
+fn lfsr_bad_args() {
+    assert!(expr_to_var("Lfsr(4, 0x3)").is_err());
+    assert!(expr_to_var("Lfsr(4, 0x1c)").is_err());
+    assert!(expr_to_var("Lfsr(4, 0xc, 0x10)").is_err());
+}
+
+#[test]
+fn checkerboard() {
+    let a = expr_to_var("Checkerboard(8)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<(u32, bool)> = vec![(0x55, false), (0xaa, true), (0x55, false), (0xaa, true)];
+    let actual: Vec<(u32, bool)> = (0..4).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn random_bits() {
+    let a = expr_to_var("RandomBits(12, [1, 3])").unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut values = HashSet::new();
+    for _ in 0..1000 {
+        let value = a.next();
+        assert!(value < 0x1000);
+        assert!((1..=3).contains(&value.count_ones()));
+        assert!(a.done());
+        values.insert(value);
+    }
+
+    assert!(values.len() > 100);
+}
+
+#[test]
+fn random_bits_constant_too_many_ones() {
+    assert!(expr_to_var("RandomBits(4, 5)").is_err());
+    assert!(expr_to_var("RandomBits(4, [0, 5])").is_err());
+    assert!(expr_to_var("RandomBits(4, [0, 4])").is_ok());
+}
+
+#[test]
+#[should_panic(expected = "which exceeds the width")]
+fn random_bits_too_many_ones() {
+    let a = expr_to_var("RandomBits(4, Pattern(5))").unwrap();
+
+    a.borrow_mut().next();
+}
+
+#[test]
+fn bad_width() {
+    assert!(expr_to_var("WalkingOnes(0)").is_err());
+    assert!(expr_to_var("Gray(33)").is_err());
+}
+
+#[test]
+fn inside_pattern_and_weighted() {
+    let a = expr_to_var("Pattern(WalkingOnes(2), Checkerboard(4))").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<(u32, bool)> = vec![(1, false), (2, false), (5, false), (0xa, true)];
+    let actual: Vec<(u32, bool)> = (0..4).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(expected, actual);
+
+    let a = expr_to_var("{Gray(2), WalkingZeros(2)}").unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut values: Vec<u32> = (0..6).map(|_| a.next()).collect();
+    values.sort();
+
+    assert_eq!(values, [0, 1, 1, 2, 2, 3]);
+    assert!(a.done());
+}
+
+#[test]
+fn host_driven_arguments() {
+    assert!(rvs::parse(&Default::default(), "input d = 4; a = Lfsr(16, d, 0xace1);").is_err());
+    assert!(rvs::parse(&Default::default(), "input d = 4; a = Lfsr(16, 0xb400, d);").is_err());
+    assert!(rvs::parse(&Default::default(), "input d = 4; a = WalkingOnes(d);").is_err());
+}
@@ -0,0 +1,98 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn fixed_lengths() {
+    let a = expr_to_var("Burst(on = 2, off = 3, active = 1, idle = 0)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<(u32, bool)> = vec![(1, false), (1, false), (0, false), (0, false), (0, true)]
+        .into_iter()
+        .cycle()
+        .take(15)
+        .collect();
+    let actual: Vec<(u32, bool)> = (0..15).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn positional() {
+    let a = expr_to_var("Burst(1, 1, Sequence(10, 20), 0)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<(u32, bool)> = vec![(10, false), (0, true), (11, false), (0, true)];
+    let actual: Vec<(u32, bool)> = (0..4).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn zero_length_phase() {
+    let a = expr_to_var("Burst(on = 0, off = 2, active = 1, idle = 0)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<(u32, bool)> = vec![(0, false), (0, true), (0, false), (0, true)];
+    let actual: Vec<(u32, bool)> = (0..4).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn lengths_redrawn_each_period() {
+    let a = expr_to_var("Burst(on = [1, 8], off = [1, 8], active = 1, idle = 0)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut on_lengths = Vec::new();
+    let mut on = 0;
+    while on_lengths.len() < 100 {
+        let value = a.next();
+        on += value;
+        if a.done() {
+            assert!((1..=8).contains(&on));
+            on_lengths.push(on);
+            on = 0;
+        }
+    }
+
+    assert!(on_lengths.iter().any(|length| *length != on_lengths[0]));
+}
+
+#[test]
+fn empty_periods_skipped() {
+    let a = expr_to_var("Burst(on = [0, 2], off = [0, 2], active = 1, idle = 0)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut length = 0;
+    for _ in 0..10000 {
+        a.next();
+        length += 1;
+        if a.done() {
+            assert!((1..=4).contains(&length));
+            length = 0;
+        }
+    }
+}
+
+#[test]
+fn both_lengths_zero() {
+    assert!(expr_to_var("Burst(on = 0, off = 0, active = 1, idle = 0)").is_err());
+    assert!(expr_to_var("Burst(on = [0, 0], off = {0, 0}, active = 1, idle = 0)").is_err());
+}
+
+#[test]
+#[should_panic(expected = "the on and off lengths of")]
+fn both_lengths_always_zero() {
+    let a = expr_to_var("Burst(on = Pattern(0), off = 0, active = 1, idle = 0)").unwrap();
+
+    a.borrow_mut().next();
+}
+
+#[test]
+fn bad_args() {
+    assert!(expr_to_var("Burst(on = 1, off = 1, active = 1)").is_err());
+    assert!(expr_to_var("Burst(on = 1, off = 1, active = 1, idle = 0, bogus = 1)").is_err());
+    assert!(expr_to_var("Burst(1, off = 1, on = 1, active = 1, idle = 0)").is_err());
+    assert!(expr_to_var("Burst(on = 1, 1, active = 1, idle = 0)").is_err());
+    assert!(expr_to_var("Burst(1, 1, 1, 0, 0)").is_err());
+}
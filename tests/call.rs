@@ -0,0 +1,81 @@
+use rvs::{Model, Parser, Transform};
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+fn transform(model: &mut Model, s: &str) -> rvs::Result<()> {
+    let mut parser = Parser::new(&Default::default());
+    parser.parse(s)?;
+
+    let mut transform = Transform::new(Default::default());
+    transform.transform(model, parser.ast())?;
+
+    Ok(())
+}
+
+#[test]
+fn lazy() {
+    let free = Rc::new(Cell::new(1));
+
+    let mut model = Model::new();
+    let value = Rc::clone(&free);
+    model.register_fn("mem_free", 0, Box::new(move |_| value.get()));
+    transform(&mut model, "len = [1, mem_free()];").unwrap();
+
+    let mut len = model.get_variable_by_name("len").unwrap().borrow_mut();
+    assert_eq!(len.next(), 1);
+
+    free.set(4);
+    let values: Vec<u32> = (0..100).map(|_| len.next()).collect();
+    assert!(values.iter().all(|value| (1..=4).contains(value)));
+    assert!(values.contains(&4));
+}
+
+#[test]
+fn args() {
+    let mut model = Model::new();
+    model.register_fn("max", 2, Box::new(|args| args[0].max(args[1])));
+    transform(&mut model, "a = max(Pattern(1, 5), 3);").unwrap();
+
+    let mut a = model.get_variable_by_name("a").unwrap().borrow_mut();
+    assert_eq!(a.next(), 3);
+    assert!(!a.done());
+    assert_eq!(a.next(), 5);
+    assert!(a.done());
+}
+
+#[test]
+fn transform_time_values() {
+    let mut model = Model::new();
+    model.register_fn("d", 0, Box::new(|_| 4));
+
+    assert!(transform(&mut model, "a = [0, d()] except {1};").is_err());
+    assert!(transform(&mut model, "a = {Expand(Pattern(1, 2), d())};").is_err());
+}
+
+#[test]
+fn unknown() {
+    let mut model = Model::new();
+
+    assert!(transform(&mut model, "a = mem_free();").is_err());
+}
+
+#[test]
+fn arity() {
+    let mut model = Model::new();
+    model.register_fn("f", 1, Box::new(|args| args[0]));
+
+    assert!(transform(&mut model, "a = f();").is_err());
+    assert!(transform(&mut model, "a = f(1, 2);").is_err());
+    assert!(transform(&mut model, "a = f(1);").is_ok());
+}
+
+#[test]
+fn display() {
+    let mut model = Model::new();
+    model.register_fn("f", 2, Box::new(|args| args[0]));
+    model.register_fn("g", 0, Box::new(|_| 0));
+    transform(&mut model, "a = 1; b = f(a, [0, 1]) + g();").unwrap();
+
+    assert_eq!(model.to_string(), "a = 0x1;\nb = (f(a, [0x0, 0x1]) + g());\n");
+}
@@ -0,0 +1,144 @@
+use std::collections::HashSet;
+
+#[test]
+fn solutions_satisfy_constraints() {
+    let model = rvs::parse(
+        &Default::default(),
+        "constraint Xfer {
+             addr = [0, 0xfff];
+             len = {1, 2, 4, 8, 16};
+             addr + len <= 0x1000;
+             addr % len == 0;
+         }",
+    ).unwrap();
+    let addr = model.get_variable_by_name("Xfer::addr").unwrap();
+    let len = model.get_variable_by_name("Xfer::len").unwrap();
+
+    let mut lens: HashSet<u32> = HashSet::new();
+    for _ in 0..1000 {
+        let addr = addr.borrow_mut().next();
+        let len = len.borrow_mut().next();
+
+        assert!(addr + len <= 0x1000);
+        assert_eq!(addr % len, 0);
+        lens.insert(len);
+    }
+
+    let expected: HashSet<u32> = [1, 2, 4, 8, 16].iter().cloned().collect();
+    assert_eq!(lens, expected);
+}
+
+#[test]
+fn fields_read_from_same_solution() {
+    let model = rvs::parse(
+        &Default::default(),
+        "constraint Pair {
+             a = [0, 1000];
+             b = [0, 1000];
+             a + b == 1000;
+         }
+         sum = Pair::a + Pair::b;",
+    ).unwrap();
+    let sum = model.get_variable_by_name("sum").unwrap();
+    let mut sum = sum.borrow_mut();
+
+    for _ in 0..100 {
+        assert_eq!(sum.next(), 1000);
+    }
+}
+
+#[test]
+fn rereading_field_solves_again() {
+    let model = rvs::parse(
+        &Default::default(),
+        "constraint C { a = [0, 0xffff]; }",
+    ).unwrap();
+    let a = model.get_variable_by_name("C::a").unwrap();
+    let mut a = a.borrow_mut();
+
+    let values: HashSet<u32> = (0..100).map(|_| a.next()).collect();
+    assert!(values.len() > 1);
+    assert!(a.done());
+}
+
+#[test]
+fn single_field_constraints_restrict_domain() {
+    let model = rvs::parse(
+        &Default::default(),
+        "constraint C { a = [0, 15] except {3}; a % 2 == 1; }",
+    ).unwrap();
+
+    assert_eq!(
+        model.to_string(),
+        "C::a = {0x1, 0x5, 0x7, 0x9, 0xb, 0xd, 0xf, };\n"
+    );
+}
+
+#[test]
+fn large_domains() {
+    let model = rvs::parse(
+        &Default::default(),
+        "constraint C {
+             a = [0, 0xffffffff];
+             b = [0, 0xffffffff];
+             a < 0x1000;
+             0xffff0000 <= b && (b & 0xf) == (a & 0xf);
+         }",
+    ).unwrap();
+    let a = model.get_variable_by_name("C::a").unwrap();
+    let b = model.get_variable_by_name("C::b").unwrap();
+
+    for _ in 0..10 {
+        let a = a.borrow_mut().next();
+        let b = b.borrow_mut().next();
+
+        assert!(a < 0x1000);
+        assert!(b >= 0xffff0000 && (b & 0xf) == (a & 0xf));
+    }
+}
+
+#[test]
+fn unsatisfiable_field() {
+    let result = rvs::parse(&Default::default(), "constraint C { a = [0, 3]; a > 3; }");
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn unsatisfiable_joint() {
+    let result = rvs::parse(&Default::default(), "constraint C { a = [0, 3]; b = [0, 3]; a + b > 6; }");
+
+    assert!(result.is_err());
+}
+
+#[test]
+#[should_panic(expected = "could not find a solution for constraint `C`")]
+fn unsatisfiable_input() {
+    let model = rvs::parse(
+        &Default::default(),
+        "input t = 0; constraint C { a = [0, 3]; b = [0, 3]; a + b > t; }",
+    ).unwrap();
+    model.set_input("t", 6);
+    let a = model.get_variable_by_name("C::a").unwrap();
+
+    a.borrow_mut().next();
+}
+
+#[test]
+fn duplicate_field() {
+    let result = rvs::parse(&Default::default(), "constraint C { a = 1; a = 2; }");
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn fields_are_scoped() {
+    let result = rvs::parse(&Default::default(), "constraint C { a = 1; } b = a;");
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn host_driven_domain() {
+    assert!(rvs::parse(&Default::default(), "input d = 4; constraint C { a = [0, d]; a < 2; }").is_err());
+}
@@ -0,0 +1,86 @@
+mod util;
+use crate::util::*;
+
+use std::collections::HashSet;
+
+#[test]
+fn yields_each_value_once_per_period() {
+    let a = expr_to_var("Cyclic([10, 25])").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert!(!a.done());
+
+    let expected: HashSet<u32> = (10..26).collect();
+    let mut periods: Vec<Vec<u32>> = Vec::new();
+
+    for _ in 0..4 {
+        let mut period: Vec<u32> = Vec::new();
+        for i in 0..16 {
+            period.push(a.next());
+            assert_eq!(a.done(), i == 15);
+        }
+
+        let actual: HashSet<u32> = period.iter().cloned().collect();
+        assert_eq!(expected, actual);
+
+        periods.push(period);
+    }
+
+    // The order is reshuffled every period
+    assert!(periods.iter().any(|period| *period != periods[0]));
+}
+
+#[test]
+fn reverse_limits() {
+    let a = expr_to_var("Cyclic([3, 0])").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: HashSet<u32> = (0..4).collect();
+    let actual: HashSet<u32> = (0..4).map(|_| a.next()).collect();
+
+    assert_eq!(expected, actual);
+    assert!(a.done());
+}
+
+#[test]
+fn equal_limits() {
+    let a = expr_to_var("Cyclic([7, 7])").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<(u32, bool)> = (0..8).map(|_| (7, true)).collect();
+    let actual: Vec<(u32, bool)> = (0..8).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn large_range() {
+    let a = expr_to_var("Cyclic([0, 0xffff_ffff])").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: HashSet<u32> = (0..0x1_0000).map(|_| a.next()).collect();
+
+    assert_eq!(actual.len(), 0x1_0000);
+    assert!(!a.done());
+}
+
+#[test]
+fn full_period_of_wide_range() {
+    let a = expr_to_var("Cyclic([0x1000_0000, 0x1001_ffff])").unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut seen = vec![false; 0x2_0000];
+    for _ in 0..0x2_0000 {
+        let value = a.next();
+        let offset = (value - 0x1000_0000) as usize;
+        assert!(!seen[offset]);
+        seen[offset] = true;
+    }
+
+    assert!(a.done());
+}
+
+#[test]
+fn host_driven_limit() {
+    assert!(rvs::parse(&Default::default(), "input d = 4; a = Cyclic([0, d]);").is_err());
+}
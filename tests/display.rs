@@ -11,3 +11,53 @@ fn precendence() {
 
     assert_eq!(model.to_string(), "a = ((0xa + 0x6) * 0x8);\n");
 }
+
+#[test]
+fn except() {
+    let model = rvs::parse(&Default::default(), "a = [0, 255] except {3, [0x10, 0x1f]};").unwrap();
+
+    assert_eq!(model.to_string(), "a = [0x0, 0xff] except {0x3, [0x10, 0x1f], };\n");
+}
+
+#[test]
+fn markov() {
+    let model = rvs::parse(&Default::default(), "a = Markov(1, 4) { 0 => 1, 1 => r{0, 1} };").unwrap();
+
+    assert_eq!(model.to_string(), "a = Markov(0x1, 0x4) {0x0 => 0x1, 0x1 => r{1: 0x0, 1: 0x1, }, };\n");
+}
+
+#[test]
+fn sequence_count() {
+    let model = rvs::parse(&Default::default(), "a = Sequence(count = 4, wrap = 3);").unwrap();
+
+    assert_eq!(
+        model.to_string(),
+        "a = Sequence(first = 0x0, count = 0x4, increment = 0x1, wrap = 0x3);\n"
+    );
+}
+
+#[test]
+fn period() {
+    let model = rvs::parse(
+        &Default::default(),
+        "a = Repeat(1, 2); b = Take(1, 2); c = Skip(1, 2); d = Cycle(1, 2);",
+    ).unwrap();
+
+    assert_eq!(
+        model.to_string(),
+        "a = Repeat(0x1, 0x2);\nb = Take(0x1, 0x2);\nc = Skip(0x1, 0x2);\nd = Cycle(0x1, 0x2);\n"
+    );
+}
+
+#[test]
+fn done_policy() {
+    let model = rvs::parse(
+        &Default::default(),
+        "a = (1 + 2).done(all); b = (1 + 2).done(any); c = (1 + 2).done(left, periodic);",
+    ).unwrap();
+
+    assert_eq!(
+        model.to_string(),
+        "a = (0x1 + 0x2);\nb = (0x1 + 0x2).done(any);\nc = (0x1 + 0x2).done(left, periodic);\n"
+    );
+}
@@ -0,0 +1,89 @@
+mod util;
+use crate::util::*;
+
+use std::collections::HashSet;
+
+#[test]
+fn range_except() {
+    let a = expr_to_var("[0, 15] except {0, 3, [8, 14]}").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: HashSet<u32> = [1, 2, 4, 5, 6, 7, 15].iter().cloned().collect();
+    let actual: HashSet<u32> = (0..200).map(|_| a.next()).collect();
+
+    assert_eq!(expected, actual);
+    assert!(a.done());
+}
+
+#[test]
+fn range_except_enum_members() {
+    let model = rvs::parse(
+        &Default::default(),
+        "enum Opcode { Nop, Halt = 3, Reserved = 0xff }
+         a = [0, 255] except {Opcode::Nop, Opcode::Halt, Opcode::Reserved};",
+    ).unwrap();
+    let a = model.get_variable_by_name("a").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..1000 {
+        let value = a.next();
+        assert!(value != 0 && value != 3 && value != 0xff);
+    }
+}
+
+#[test]
+fn range_except_everything() {
+    assert!(expr_to_var("[0, 3] except {[0, 1], [2, 3]}").is_err());
+}
+
+#[test]
+fn weighted_except() {
+    let a = expr_to_var("r{1, 2, 4, 8} except {2, 8}").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: HashSet<u32> = [1, 4].iter().cloned().collect();
+    let actual: HashSet<u32> = (0..100).map(|_| a.next()).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn weighted_except_range_member() {
+    let a = expr_to_var("{0, [1, 4]} except {[0, 2]}").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: HashSet<u32> = [3, 4].iter().cloned().collect();
+    let actual: HashSet<u32> = (0..100).map(|_| a.next()).collect();
+
+    assert_eq!(expected, actual);
+    assert!(a.done());
+}
+
+#[test]
+fn weighted_except_expand() {
+    let a = expr_to_var("{Expand(Sequence(7))} except {[2, 5]}").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..4 {
+        let mut actual: Vec<u32> = (0..4).map(|_| a.next()).collect();
+        actual.sort();
+
+        assert_eq!(actual, [0, 1, 6, 7]);
+        assert!(a.done());
+    }
+}
+
+#[test]
+fn weighted_except_everything() {
+    assert!(expr_to_var("r{1, [2, 3]} except {[1, 3]}").is_err());
+}
+
+#[test]
+fn weighted_except_non_constant_member() {
+    assert!(expr_to_var("r{1, Pattern(2, 3)} except {2}").is_err());
+}
+
+#[test]
+fn host_driven_excluded() {
+    assert!(rvs::parse(&Default::default(), "input d = 4; a = [0, 4] except {d};").is_err());
+}
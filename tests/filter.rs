@@ -0,0 +1,73 @@
+mod util;
+use crate::util::*;
+
+use std::collections::HashSet;
+
+#[test]
+fn predicate_holds() {
+    let a = expr_to_var("Where([0, 0xffff], _ % 3 != 0 && _ != 0x100)").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..1000 {
+        let value = a.next();
+        assert!(!value.is_multiple_of(3) && value != 0x100);
+        assert!(a.done());
+    }
+}
+
+#[test]
+fn doneness_from_accepted_draw() {
+    let a = expr_to_var("Where(Pattern(0, 1, 2, 3, 4, 5), _ % 2 == 0)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<(u32, bool)> = vec![(0, false), (2, false), (4, false)]
+        .into_iter()
+        .cycle()
+        .take(12)
+        .collect();
+    let actual: Vec<(u32, bool)> = (0..12).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn nested() {
+    let a = expr_to_var("Where(Where([0, 15], _ > 3), _ < 8)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: HashSet<u32> = (4..8).collect();
+    let actual: HashSet<u32> = (0..100).map(|_| a.next()).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn placeholder_outside_where() {
+    assert!(expr_to_var("_ + 1").is_err());
+}
+
+#[test]
+fn constant_predicate() {
+    assert!(expr_to_var("Where([0, 3], 0)").is_err());
+    assert!(expr_to_var("Where([0, 3], 1 > 2)").is_err());
+    assert!(expr_to_var("Where([0, 3], 1 < 2)").is_ok());
+}
+
+#[test]
+fn zero_limit() {
+    assert!(expr_to_var("Where([0, 1], _ == 0, 0)").is_err());
+}
+
+#[test]
+#[should_panic(expected = "the predicate `(_ > 0x3)` rejected 10 consecutive values in the expression `Where([0x0, 0x3], (_ > 0x3), 10)`")]
+fn limit_exceeded() {
+    let a = expr_to_var("Where([0, 3], _ > 3, 10)").unwrap();
+    let mut a = a.borrow_mut();
+
+    a.next();
+}
+
+#[test]
+fn host_driven_limit() {
+    assert!(rvs::parse(&Default::default(), "input d = 4; a = Where([0, 3], _ > 0, d);").is_err());
+}
@@ -0,0 +1,101 @@
+mod util;
+use crate::util::*;
+
+use std::collections::HashSet;
+
+fn parse_var(s: &str, name: &str) -> rvs::Result<u32> {
+    let model = rvs::parse(&Default::default(), s)?;
+    let variable = model.get_variable_by_name(name).unwrap();
+    let value = variable.borrow_mut().next();
+
+    Ok(value)
+}
+
+#[test]
+fn implicit_values() {
+    let s = "flags Perm { R, W, X } a = Perm::R; b = Perm::W; c = Perm::X;";
+
+    assert_eq!(parse_var(s, "a").unwrap(), 1);
+    assert_eq!(parse_var(s, "b").unwrap(), 2);
+    assert_eq!(parse_var(s, "c").unwrap(), 4);
+}
+
+#[test]
+fn explicit_values() {
+    let s = "flags Irq { Timer, Uart = 0x10, Spi, None = 0, Gpio } a = Irq::Spi; b = Irq::Gpio;";
+
+    assert_eq!(parse_var(s, "a").unwrap(), 0x20);
+    assert_eq!(parse_var(s, "b").unwrap(), 1);
+}
+
+#[test]
+fn combinable() {
+    let s = "flags Perm { R, W, X } a = Perm::R | Perm::X;";
+
+    assert_eq!(parse_var(s, "a").unwrap(), 5);
+}
+
+#[test]
+fn too_many_members() {
+    let s = "flags Wide { A = 0x80000000, B } a = Wide::B;";
+
+    assert!(parse_var(s, "a").is_err());
+}
+
+#[test]
+fn combo() {
+    let model = rvs::parse(
+        &Default::default(),
+        "flags Perm { R, W, X, D = 0x100 }
+         a = Combo(Perm, 1, 2);",
+    ).unwrap();
+    let a = model.get_variable_by_name("a").unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut values = HashSet::new();
+    for _ in 0..1000 {
+        let value = a.next();
+        assert_eq!(value & !0x107, 0);
+        assert!((1..=2).contains(&value.count_ones()));
+        assert!(a.done());
+        values.insert(value);
+    }
+
+    // 4 singles + 6 pairs
+    assert_eq!(values.len(), 10);
+}
+
+#[test]
+fn combo_defaults() {
+    let a = expr_to_var("Combo({1, 2, 4})").unwrap();
+    let mut a = a.borrow_mut();
+
+    let values: HashSet<u32> = (0..1000).map(|_| a.next()).collect();
+    let expected: HashSet<u32> = (0..8).collect();
+
+    assert_eq!(values, expected);
+}
+
+#[test]
+fn combo_requires_flags() {
+    let s = "enum Color { Red, Green } a = Combo(Color);";
+
+    assert!(parse_var(s, "a").is_err());
+    assert!(expr_to_var("Combo([0, 1])").is_err());
+}
+
+#[test]
+fn combo_constant_limits() {
+    assert!(expr_to_var("Combo({1, 2}, 0, 3)").is_err());
+    assert!(expr_to_var("Combo({1, 2}, 2, 1)").is_err());
+    assert!(expr_to_var("Combo({1, 2}, 3)").is_err());
+    assert!(expr_to_var("Combo({1, 2}, 2)").is_ok());
+}
+
+#[test]
+#[should_panic(expected = "expected 0 <= min <= max <= 2")]
+fn combo_max_too_large() {
+    let a = expr_to_var("Combo({1, 2}, 0, Pattern(3))").unwrap();
+
+    a.borrow_mut().next();
+}
@@ -0,0 +1,77 @@
+fn parse(s: &str) -> rvs::Model {
+    rvs::parse(&Default::default(), s).unwrap()
+}
+
+#[test]
+fn prev_n() {
+    let model = parse("a = Sequence(10); b = a.prev(1); c = a.prev(2); d = a.prev(3);");
+    let a = model.get_variable_by_name("a").unwrap();
+
+    for _ in 0..5 {
+        a.borrow_mut().next();
+    }
+
+    let values: Vec<u32> = ["b", "c", "d"]
+        .iter()
+        .map(|name| model.get_variable_by_name(name).unwrap().borrow_mut().next())
+        .collect();
+    assert_eq!(values, [4, 3, 2]);
+}
+
+#[test]
+fn depth_grows_to_largest_reference() {
+    let model = parse("a = 1; b = a.prev(2); c = a.prev(4);");
+    let a = model.get_variable_by_name("a").unwrap();
+
+    assert_eq!(a.borrow().history_depth(), 4);
+}
+
+#[test]
+fn history() {
+    let model = parse("a = Sequence(10);");
+    let a = model.get_variable_by_name("a").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert_eq!(a.history_depth(), 1);
+    a.set_history_depth(2);
+
+    a.next();
+    assert_eq!(a.history(1), 0);
+    assert_eq!(a.history(2), 0);
+
+    a.next();
+    a.next();
+    assert_eq!(a.history(1), 2);
+    assert_eq!(a.history(2), 1);
+    assert_eq!(a.history(3), 0);
+}
+
+#[test]
+fn no_repeat_of_last_two() {
+    let model = parse("a = [0, 3]; b = a; c = Where([0, 3], _ != b.prev(1) && _ != b.prev(2));");
+    let b = model.get_variable_by_name("b").unwrap();
+    let c = model.get_variable_by_name("c").unwrap();
+
+    for _ in 0..100 {
+        let mut b = b.borrow_mut();
+        b.next();
+        b.next();
+        let (last, second_last) = (b.history(1), b.history(2));
+        drop(b);
+
+        let value = c.borrow_mut().next();
+        assert!(value != last && value != second_last);
+    }
+}
+
+#[test]
+fn zero() {
+    assert!(rvs::parse(&Default::default(), "a = 1; b = a.prev(0);").is_err());
+}
+
+#[test]
+fn display() {
+    let model = parse("a = 1; b = a.prev(3);");
+
+    assert_eq!(model.to_string(), "a = 0x1;\nb = a.prev(3);\n");
+}
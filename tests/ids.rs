@@ -0,0 +1,74 @@
+mod util;
+use crate::util::*;
+
+use std::collections::HashSet;
+
+#[test]
+fn unique_until_exhausted() {
+    let a = expr_to_var("Ids(10, 25)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut ids = HashSet::new();
+    for i in 0..16 {
+        let id = a.next();
+        assert!((10..=25).contains(&id));
+        assert!(ids.insert(id));
+        assert_eq!(a.done(), i == 15);
+    }
+}
+
+#[test]
+fn release() {
+    let a = expr_to_var("Ids(lo = 0, hi = 3)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let ids: Vec<u32> = (0..4).map(|_| a.next()).collect();
+    assert!(a.done());
+
+    assert!(a.release(ids[2]));
+    assert!(!a.release(ids[2]));
+    assert!(!a.release(4));
+
+    assert_eq!(a.next(), ids[2]);
+    assert!(a.done());
+}
+
+#[test]
+fn exhausted() {
+    let a = expr_to_var("Ids(7, 7)").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert_eq!(a.next(), 7);
+    assert!(!a.exhausted());
+
+    assert_eq!(a.next(), 7);
+    assert!(a.exhausted());
+    assert!(a.done());
+
+    assert!(a.release(7));
+    assert_eq!(a.next(), 7);
+    assert!(!a.exhausted());
+}
+
+#[test]
+fn model_release() {
+    let model = rvs::parse(&Default::default(), "a = Ids(0, 0); b = 1;").unwrap();
+
+    let id = model.get_variable_by_name("a").unwrap().borrow_mut().next();
+    assert!(model.release("a", id));
+    assert!(!model.release("a", id));
+    assert!(!model.release("b", 1));
+    assert!(!model.release("c", 0));
+}
+
+#[test]
+fn display() {
+    let model = rvs::parse(&Default::default(), "a = Ids(1, 0x10);").unwrap();
+
+    assert_eq!(model.to_string(), "a = Ids(0x1, 0x10);\n");
+}
+
+#[test]
+fn host_driven_limits() {
+    assert!(rvs::parse(&Default::default(), "input d = 4; a = Ids(0, d);").is_err());
+}
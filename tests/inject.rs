@@ -0,0 +1,97 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn flip() {
+    let a = expr_to_var("Inject(0, 100, flip(3))").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..100 {
+        assert_eq!(a.next().count_ones(), 3);
+        assert!(a.injected());
+    }
+}
+
+#[test]
+fn flip_within_width() {
+    let model = rvs::parse(&Default::default(), "a: u4 = Inject(0, 100, flip(4));").unwrap();
+    let mut a = model.get_variable_by_name("a").unwrap().borrow_mut();
+
+    for _ in 0..10 {
+        assert_eq!(a.next(), 0xf);
+    }
+}
+
+#[test]
+fn xor() {
+    let a = expr_to_var("Inject(0xf0, 100, xor(0xff))").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert_eq!(a.next(), 0x0f);
+}
+
+#[test]
+fn percent() {
+    let a = expr_to_var("Inject(Sequence(3), 25, replace(0xbad))").unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut injected = 0;
+    for i in 0..1000 {
+        let value = a.next();
+        if a.injected() {
+            assert_eq!(value, 0xbad);
+            injected += 1;
+        } else {
+            assert_eq!(value, i % 4);
+        }
+        assert_eq!(a.done(), i % 4 == 3);
+    }
+
+    assert!(injected > 150 && injected < 350, "{}", injected);
+}
+
+#[test]
+fn flip_count_exceeds_width() {
+    assert!(expr_to_var("Inject(0, 100, flip(33))").is_err());
+    assert!(expr_to_var("Inject(0, 100, flip(0))").is_err());
+    assert!(rvs::parse(&Default::default(), "a: u4 = Inject(0, 100, flip(5));").is_err());
+    assert!(expr_to_var("Packed { a: 4 = Inject(1, 100, flip(5)), b: 28 = 0 }").is_err());
+}
+
+#[test]
+#[should_panic(expected = "returned 33 which is not in [1, 32]")]
+fn flip_too_many() {
+    let a = expr_to_var("Inject(0, 100, flip(Pattern(33)))").unwrap();
+    let mut a = a.borrow_mut();
+
+    a.next();
+}
+
+#[test]
+fn flip_packed_field() {
+    let a = expr_to_var("Packed { a: 4 = Inject(1, 100, flip(1)), b: 28 = 0 }").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..1000 {
+        let value = a.next();
+        assert_eq!(value & 0x0fff_ffff, 0);
+        assert!([0x0, 0x3, 0x5, 0x9].contains(&(value >> 28)), "0x{:x}", value);
+    }
+}
+
+#[test]
+fn percent_too_large() {
+    assert!(expr_to_var("Inject(0, 101, flip(1))").is_err());
+}
+
+#[test]
+fn display() {
+    let model = rvs::parse(&Default::default(), "a = Inject([0, 3], 10, xor(1 << 2));").unwrap();
+
+    assert_eq!(model.to_string(), "a = Inject([0x0, 0x3], 10, xor((0x1 << 0x2)));\n");
+}
+
+#[test]
+fn host_driven_percent() {
+    assert!(rvs::parse(&Default::default(), "input d = 4; a = Inject(0, d, flip(1));").is_err());
+}
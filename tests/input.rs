@@ -0,0 +1,92 @@
+#[test]
+fn set_input() {
+    let model = rvs::parse(
+        &Default::default(),
+        "input depth = 3; a = depth; b = depth.prev + 1;",
+    ).unwrap();
+
+    let a = model.get_variable_by_name("a").unwrap();
+    let b = model.get_variable_by_name("b").unwrap();
+
+    assert_eq!(a.borrow_mut().next(), 3);
+    assert_eq!(b.borrow_mut().next(), 4);
+
+    assert!(model.set_input("depth", 7));
+    assert_eq!(a.borrow_mut().next(), 7);
+    assert_eq!(b.borrow_mut().next(), 8);
+}
+
+#[test]
+fn input_value() {
+    let model = rvs::parse(&Default::default(), "input phase = 1 + 1;").unwrap();
+    let mut phase = model.get_variable_by_name("phase").unwrap().borrow_mut();
+
+    assert!(phase.is_input());
+    assert_eq!(phase.next(), 2);
+    assert!(phase.done());
+    assert!(phase.set(9));
+    assert_eq!(phase.next(), 9);
+    assert_eq!(phase.prev(), 9);
+}
+
+#[test]
+fn set_non_input() {
+    let model = rvs::parse(&Default::default(), "input depth = 0; a = 1;").unwrap();
+
+    assert!(!model.set_input("a", 1));
+    assert!(!model.set_input("b", 1));
+    assert!(!model.get_variable_by_name("a").unwrap().borrow().is_input());
+}
+
+#[test]
+fn range_limit() {
+    let model = rvs::parse(&Default::default(), "input depth = 0; a = [0, depth];").unwrap();
+    let a = model.get_variable_by_name("a").unwrap();
+
+    assert_eq!(a.borrow_mut().next(), 0);
+    model.set_input("depth", 3);
+
+    let values: Vec<u32> = (0..100).map(|_| a.borrow_mut().next()).collect();
+    assert!(values.iter().all(|value| *value <= 3));
+    assert!(values.contains(&3));
+    assert_eq!(model.to_string(), "input depth = 0x3;\na = [0x0, depth];\n");
+}
+
+#[test]
+fn sequence_last() {
+    let model = rvs::parse(&Default::default(), "input depth = 0; a = Sequence(depth);").unwrap();
+    let a = model.get_variable_by_name("a").unwrap();
+    model.set_input("depth", 2);
+
+    let values: Vec<u32> = (0..6).map(|_| a.borrow_mut().next()).collect();
+    assert_eq!(values, vec![0, 1, 2, 0, 1, 2]);
+}
+
+#[test]
+fn transform_time_values() {
+    let parse = |s: &str| rvs::parse(&Default::default(), &format!("input d = 4; {}", s));
+
+    assert!(parse("a = [0, d] except {1};").is_err());
+    assert!(parse("a = {Expand(Pattern(1, 2), d)};").is_err());
+    assert!(parse("a = Repeat(5, d);").is_ok());
+}
+
+#[test]
+fn redefine() {
+    let model = rvs::parse(&Default::default(), "a = 1; input a = 2;").unwrap();
+
+    assert!(model.set_input("a", 3));
+}
+
+#[test]
+fn copy() {
+    assert!(rvs::parse(&Default::default(), "input a = 0; b = a.copy;").is_err());
+}
+
+#[test]
+fn display() {
+    let model = rvs::parse(&Default::default(), "input depth = 2; inputs = depth;").unwrap();
+    model.set_input("depth", 5);
+
+    assert_eq!(model.to_string(), "input depth = 0x5;\ninputs = depth;\n");
+}
@@ -0,0 +1,62 @@
+use std::env;
+
+fn next(model: &rvs::Model, name: &str) -> u32 {
+    model.get_variable_by_name(name).unwrap().borrow_mut().next()
+}
+
+#[test]
+fn arg() {
+    let model = rvs::parse_with_args(
+        &Default::default(),
+        "a = arg(\"max_len\", 64); b = arg(\"min_len\", 1 + 1); c = [0, arg(\"hi\", 0)];",
+        &[("max_len", " 4K "), ("hi", "8'h0")],
+    ).unwrap();
+
+    assert_eq!(next(&model, "a"), 4096);
+    assert_eq!(next(&model, "b"), 2);
+    assert_eq!(next(&model, "c"), 0);
+}
+
+#[test]
+fn arg_default() {
+    let model = rvs::parse(&Default::default(), "a = arg(\"max_len\", 64);").unwrap();
+
+    assert_eq!(next(&model, "a"), 64);
+}
+
+#[test]
+fn arg_not_a_number() {
+    let result = rvs::parse_with_args(
+        &Default::default(),
+        "a = arg(\"max_len\", 64);",
+        &[("max_len", "long")],
+    );
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn env() {
+    env::set_var("RVS_TEST_LOOKUP_ENV", "0x20");
+    env::set_var("RVS_TEST_LOOKUP_ENV_BAD", "0x");
+
+    let model = rvs::parse(
+        &Default::default(),
+        "a = env(\"RVS_TEST_LOOKUP_ENV\", 64); b = env(\"RVS_TEST_LOOKUP_ENV_MISSING\", 64);",
+    ).unwrap();
+
+    assert_eq!(next(&model, "a"), 0x20);
+    assert_eq!(next(&model, "b"), 64);
+    assert!(rvs::parse(&Default::default(), "a = env(\"RVS_TEST_LOOKUP_ENV_BAD\", 64);").is_err());
+}
+
+#[test]
+fn display() {
+    let model = rvs::parse_with_args(
+        &Default::default(),
+        "a = arg(\"x\", 1); b = arg(\"y\", [0, 1]);",
+        &[("x", "5")],
+    ).unwrap();
+
+    assert_eq!(model.to_string(), "a = 0x5;\nb = [0x0, 0x1];\n");
+}
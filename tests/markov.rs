@@ -0,0 +1,120 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn deterministic() {
+    let a = expr_to_var("Markov { 0 => 1, 1 => 2, 2 => 0 }").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<(u32, bool)> = vec![(0, false), (1, false), (2, true)]
+        .into_iter()
+        .cycle()
+        .take(9)
+        .collect();
+    let actual: Vec<(u32, bool)> = (0..9).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn initial_state() {
+    let a = expr_to_var("Markov(1) { 0 => 1, 1 => 2, 2 => 0 }").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<(u32, bool)> = vec![(1, false), (2, false), (0, true)]
+        .into_iter()
+        .cycle()
+        .take(9)
+        .collect();
+    let actual: Vec<(u32, bool)> = (0..9).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn count() {
+    let a = expr_to_var("Markov(0, 2) { 0 => 1, 1 => 2, 2 => 0 }").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<(u32, bool)> = vec![
+        (0, false), (1, true),
+        (2, false), (0, true),
+        (1, false), (2, true),
+    ];
+    let actual: Vec<(u32, bool)> = (0..6).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn transitions_follow_previous_state() {
+    let model = rvs::parse(
+        &Default::default(),
+        "enum Op { Idle, Read, Write }
+         a = Markov {
+             Op::Idle => r{Op::Read, Op::Write},
+             Op::Read => r{50: Op::Read, 50: Op::Idle},
+             Op::Write => r{70: Op::Write, 30: Op::Idle},
+         };",
+    ).unwrap();
+    let a = model.get_variable_by_name("a").unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut write_write = 0;
+    let mut write_idle = 0;
+    let mut prev = a.next();
+    let mut prev_done = a.done();
+    assert_eq!(prev, 0);
+    for _ in 0..10000 {
+        let next = a.next();
+        assert_eq!(prev_done, next == 0);
+        match (prev, next) {
+            (0, 0) => panic!("Idle must not follow Idle"),
+            (1, 2) | (2, 1) => panic!("Read and Write must be separated by Idle"),
+            (2, 2) => write_write += 1,
+            (2, 0) => write_idle += 1,
+            _ => {}
+        }
+        prev = next;
+        prev_done = a.done();
+    }
+
+    let ratio = f64::from(write_write) / f64::from(write_write + write_idle);
+    assert!(ratio > 0.65 && ratio < 0.75);
+}
+
+#[test]
+fn missing_range_transition() {
+    assert!(expr_to_var("Markov { 0 => [1, 2], 1 => 0 }").is_err());
+    assert!(expr_to_var("Markov { 0 => [0, 0xffff_ffff], 1 => 0 }").is_err());
+    assert!(expr_to_var("Markov { 0 => [1, 2], 1 => 0, 2 => 0 }").is_ok());
+    assert!(rvs::parse(&Default::default(), "a: i8 = Markov { -1 => [-1, 0], 0 => -1 };").is_ok());
+}
+
+#[test]
+#[should_panic(expected = "the state 0x2 has no transition")]
+fn missing_transition() {
+    let a = expr_to_var("Markov { 0 => Pattern(1, 2), 1 => 0 }").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..1000 {
+        a.next();
+    }
+}
+
+#[test]
+fn missing_transition_for_constant_state() {
+    assert!(expr_to_var("Markov { 0 => 1, 1 => 2 }").is_err());
+    assert!(expr_to_var("Markov { 0 => r{1, 2}, 1 => 0 }").is_err());
+    assert!(expr_to_var("Markov(2) { 0 => 1, 1 => 0 }").is_err());
+}
+
+#[test]
+fn duplicate_state() {
+    assert!(expr_to_var("Markov { 0 => 1, 1 => 0, 0 => 0 }").is_err());
+}
+
+#[test]
+fn non_constant_state() {
+    assert!(expr_to_var("Markov { [0, 1] => 1, 1 => 0 }").is_err());
+}
@@ -0,0 +1,88 @@
+mod util;
+use crate::util::*;
+
+fn values(expr: &str, count: usize) -> Vec<u32> {
+    let a = expr_to_var(expr).unwrap();
+    let mut a = a.borrow_mut();
+
+    (0..count).map(|_| a.next()).collect()
+}
+
+fn assert_no_repeat(values: &[u32], window: usize) {
+    for (i, value) in values.iter().enumerate() {
+        let start = i.saturating_sub(window);
+        assert!(!values[start..i].contains(value), "{:?} at {}", values, i);
+    }
+}
+
+#[test]
+fn range() {
+    let values = values("NoRepeat([0, 2], 2)", 100);
+
+    assert_no_repeat(&values, 2);
+    assert!(values.iter().all(|value| *value <= 2));
+}
+
+#[test]
+fn large_window() {
+    let values = values("NoRepeat([0, 0xffff_ffff], 0xffff_fff0)", 1000);
+
+    assert_no_repeat(&values, 1000);
+}
+
+#[test]
+fn weighted() {
+    let values = values("NoRepeat(r{10: 1, 1: 2, 1: 3}, 1)", 100);
+
+    assert_no_repeat(&values, 1);
+    assert!(values.iter().all(|value| (1..=3).contains(value)));
+}
+
+#[test]
+fn retry() {
+    let values = values("NoRepeat([0, 3] + 4, 3)", 100);
+
+    assert_no_repeat(&values, 3);
+    assert!(values.iter().all(|value| (4..=7).contains(value)));
+}
+
+#[test]
+fn done() {
+    let a = expr_to_var("NoRepeat(Pattern(1, 2), 1)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<(u32, bool)> = (0..4).map(|_| (a.next(), a.done())).collect();
+    assert_eq!(actual, [(1, false), (2, true), (1, false), (2, true)]);
+}
+
+#[test]
+#[should_panic(expected = "could not draw a value that differs from the last 1 values")]
+fn retry_limit() {
+    values("NoRepeat(5 + 0, 1)", 2);
+}
+
+#[test]
+fn domain_too_small() {
+    assert!(expr_to_var("NoRepeat([0, 2], 3)").is_err());
+    assert!(expr_to_var("NoRepeat(r{1, 2, 0: 3}, 2)").is_err());
+    assert!(expr_to_var("NoRepeat([0, 2], 0)").is_err());
+}
+
+#[test]
+fn display() {
+    let model = rvs::parse(
+        &Default::default(),
+        "a = NoRepeat([0, 3], 2); b = NoRepeat(r{1, 2}, 1); c = NoRepeat(Pattern(1, 2), 1);",
+    ).unwrap();
+
+    assert_eq!(
+        model.to_string(),
+        "a = NoRepeat([0x0, 0x3], 2);\nb = NoRepeat(r{1: 0x1, 1: 0x2, }, 1);\nc = NoRepeat(Pattern(0x1, 0x2, ), 1);\n"
+    );
+}
+
+#[test]
+fn host_driven_window() {
+    assert!(rvs::parse(&Default::default(), "input d = 4; a = NoRepeat([0, 9], d);").is_err());
+    assert!(rvs::parse(&Default::default(), "input d = 4; a = NoRepeat([0, d], 1);").is_ok());
+}
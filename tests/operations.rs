@@ -71,3 +71,81 @@ fn overflow_neg() {
 
     assert_eq!(0, a.next());
 }
+
+#[test]
+fn comparisons() {
+    let cases = [
+        ("1 == 1", 1),
+        ("1 == 2", 0),
+        ("1 != 2", 1),
+        ("1 < 2", 1),
+        ("2 < 1", 0),
+        ("2 <= 2", 1),
+        ("3 > 2", 1),
+        ("2 >= 3", 0),
+    ];
+
+    for &(expr, expected) in cases.iter() {
+        let a = expr_to_var(expr).unwrap();
+        assert_eq!(a.borrow_mut().next(), expected, "{}", expr);
+    }
+}
+
+#[test]
+fn logical() {
+    let cases = [
+        ("1 && 2", 1),
+        ("1 && 0", 0),
+        ("0 || 2", 1),
+        ("0 || 0", 0),
+        ("!0", 1),
+        ("!5", 0),
+        ("1 | 2 == 2", 1),
+        ("1 < 2 && 3 > 4 || 5 == 5", 1),
+    ];
+
+    for &(expr, expected) in cases.iter() {
+        let a = expr_to_var(expr).unwrap();
+        assert_eq!(a.borrow_mut().next(), expected, "{}", expr);
+    }
+}
+
+fn doneness(expr: &str, count: usize) -> Vec<bool> {
+    let a = expr_to_var(expr).unwrap();
+    let mut a = a.borrow_mut();
+
+    (0..count).map(|_| { a.next(); a.done() }).collect()
+}
+
+#[test]
+fn done_policy() {
+    let cases = [
+        ("(Pattern(0, 1) + Pattern(0, 1, 2)).done(all)", [false, false, true, true, true, true]),
+        ("(Pattern(0, 1) + Pattern(0, 1, 2)).done(any)", [false, true, true, true, true, true]),
+        ("(Pattern(0, 1) + Pattern(0, 1, 2)).done(left)", [false, true, true, true, true, true]),
+        ("(Pattern(0, 1) + Pattern(0, 1, 2)).done(right)", [false, false, true, true, true, true]),
+        ("(Pattern(0, 1) + Pattern(0, 1, 2)).done(all, periodic)", [false, false, true, false, false, true]),
+        ("(Pattern(0, 1) + Pattern(0, 1, 2)).done(any, periodic)", [false, true, true, true, false, true]),
+        ("(Pattern(0, 1) + Pattern(0, 1, 2)).done(left, periodic)", [false, true, false, true, false, true]),
+        ("(Pattern(0, 1) + Pattern(0, 1, 2)).done(right, sticky)", [false, false, true, true, true, true]),
+    ];
+
+    for &(expr, expected) in cases.iter() {
+        assert_eq!(doneness(expr, 6), expected, "{}", expr);
+    }
+}
+
+#[test]
+fn done_policy_in_pattern() {
+    let a = expr_to_var("Pattern((Pattern(1, 2) + 0).done(left, periodic), 9)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let values: Vec<u32> = (0..6).map(|_| a.next()).collect();
+    assert_eq!(values, [1, 2, 9, 1, 2, 9]);
+}
+
+#[test]
+fn done_policy_requires_operation() {
+    assert!(expr_to_var("Pattern(1, 2).done(any)").is_err());
+    assert!(expr_to_var("(1).done(bogus)").is_err());
+}
@@ -0,0 +1,97 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn packs_fields_msb_first() {
+    let a = expr_to_var("Packed { opcode: 4 = 0xa, len: 12 = 0x123, rsvd: 16 = 0xbeef }").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert_eq!(a.next(), 0xa123_beef);
+    assert!(a.done());
+}
+
+#[test]
+fn field_views() {
+    let model = rvs::parse(
+        &Default::default(),
+        "enum Cmd { Read, Write }
+         packed Hdr { opcode: 4 = {Cmd::Read, Cmd::Write}, len: 12 = [1, 4095], rsvd: 16 = 0 }",
+    ).unwrap();
+    let hdr = model.get_variable_by_name("Hdr").unwrap();
+    let opcode = model.get_variable_by_name("Hdr::opcode").unwrap();
+    let len = model.get_variable_by_name("Hdr::len").unwrap();
+
+    for _ in 0..100 {
+        let value = hdr.borrow_mut().next();
+        let opcode = opcode.borrow_mut().next();
+        let len = len.borrow_mut().next();
+
+        assert!(opcode <= 1);
+        assert!((1..=4095).contains(&len));
+        assert_eq!(value, opcode << 28 | len << 16);
+    }
+}
+
+#[test]
+fn done_when_all_fields_done() {
+    let a = expr_to_var("Packed { a: 8 = Sequence(1), b: 8 = Sequence(3) }").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<(u32, bool)> = vec![
+        (0x0000, false),
+        (0x0101, false),
+        (0x0002, false),
+        (0x0103, true),
+    ];
+    let actual: Vec<(u32, bool)> = (0..4).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn display_round_trip() {
+    let s = "packed Hdr { opcode: 4 = 3, len: 12 = 0x10, rsvd: 16 = 0 }";
+    let model = rvs::parse(&Default::default(), s).unwrap();
+    let reparsed = rvs::parse(&Default::default(), &model.to_string()).unwrap();
+
+    assert_eq!(model.to_string(), reparsed.to_string());
+
+    let hdr = reparsed.get_variable_by_name("Hdr").unwrap();
+    let len = reparsed.get_variable_by_name("Hdr::len").unwrap();
+    assert_eq!(hdr.borrow_mut().next(), 0x3010_0000);
+    assert_eq!(len.borrow_mut().next(), 0x10);
+}
+
+#[test]
+fn constant_truncated_with_warning() {
+    let model = rvs::parse(&Default::default(), "a = Packed { a: 4 = 0x13, b: 4 = 0x5 };").unwrap();
+    let a = model.get_variable_by_name("a").unwrap();
+
+    assert_eq!(a.borrow_mut().next(), 0x35);
+    assert_eq!(
+        model.warnings(),
+        ["The value 0x13 of field 'a' does not fit in 4 bits and was truncated to 0x3"]
+    );
+}
+
+#[test]
+fn constant_too_wide() {
+    assert!(expr_to_var("Packed { a: 4 = [0, 0x10] }").is_err());
+    assert!(expr_to_var("Packed { a: 4 = {1, 0x1f} }").is_err());
+}
+
+#[test]
+fn too_wide() {
+    assert!(expr_to_var("Packed { a: 16 = 0, b: 17 = 0 }").is_err());
+    assert!(expr_to_var("Packed { a: 0 = 0 }").is_err());
+    assert!(expr_to_var("Packed { a: 1 = 0, a: 1 = 0 }").is_err());
+}
+
+#[test]
+fn dynamic_overflow_truncated() {
+    let a = expr_to_var("Packed { a: 4 = Pattern(0xf, 0x13), b: 4 = 0x5 }").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert_eq!(a.next(), 0xf5);
+    assert_eq!(a.next(), 0x35);
+}
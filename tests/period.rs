@@ -0,0 +1,110 @@
+mod util;
+use crate::util::*;
+
+fn take(name: &str, count: usize) -> Vec<(u32, bool)> {
+    let a = expr_to_var(name).unwrap();
+    let mut a = a.borrow_mut();
+
+    (0..count).map(|_| (a.next(), a.done())).collect()
+}
+
+#[test]
+fn repeat() {
+    let expected: Vec<(u32, bool)> = vec![
+        (1, false),
+        (1, false),
+        (2, false),
+        (2, true),
+        (1, false),
+        (1, false),
+    ];
+
+    assert_eq!(take("Repeat(Pattern(1, 2), 2)", 6), expected);
+}
+
+#[test]
+fn repeat_reevaluates_count() {
+    let expected: Vec<(u32, bool)> = vec![
+        (5, false),
+        (5, true),
+        (5, false),
+        (5, false),
+        (5, true),
+    ];
+
+    assert_eq!(take("Repeat(5, Pattern(2, 3))", 5), expected);
+}
+
+#[test]
+fn pattern_of_bursts() {
+    let expected: Vec<u32> = vec![1, 1, 1, 0, 0, 1, 1, 1];
+    let actual: Vec<u32> = take("Pattern(Repeat(1, 3), Repeat(0, 2))", 8)
+        .iter()
+        .map(|(value, _)| *value)
+        .collect();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn take_ignores_inner_doneness() {
+    let expected: Vec<(u32, bool)> = vec![
+        (0, false),
+        (1, false),
+        (2, true),
+        (3, false),
+        (4, false),
+        (5, false),
+        (6, true),
+    ];
+
+    assert_eq!(take("Take(Sequence(8), Pattern(3, 4))", 7), expected);
+}
+
+#[test]
+fn skip() {
+    let expected: Vec<(u32, bool)> = vec![
+        (2, false),
+        (3, true),
+        (2, false),
+        (3, true),
+    ];
+
+    assert_eq!(take("Skip(Sequence(3), 2)", 4), expected);
+}
+
+#[test]
+fn skip_none() {
+    let expected: Vec<(u32, bool)> = vec![(0, false), (1, true), (0, false)];
+
+    assert_eq!(take("Skip(Sequence(1), 0)", 3), expected);
+}
+
+#[test]
+fn cycle() {
+    let expected: Vec<(u32, bool)> = vec![
+        (0, false),
+        (1, false),
+        (0, false),
+        (1, true),
+        (0, false),
+    ];
+
+    assert_eq!(take("Cycle(Sequence(1), 2)", 5), expected);
+}
+
+#[test]
+fn named_args() {
+    assert_eq!(take("Take(expr = 7, count = 1)", 1), vec![(7, true)]);
+}
+
+#[test]
+#[should_panic(expected = "the count sub-expression `0x0` returned 0")]
+fn zero_count() {
+    take("Repeat(1, 0)", 1);
+}
+
+#[test]
+fn missing_count() {
+    assert!(expr_to_var("Cycle(1)").is_err());
+}
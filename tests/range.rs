@@ -42,3 +42,12 @@ fn done_after_each_next() {
 
     assert_eq!(expected, actual);
 }
+
+#[test]
+fn equal_limits_nonzero() {
+    let a = expr_to_var("[5, 5]").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert_eq!(a.next(), 5);
+    assert!(a.done());
+}
@@ -74,3 +74,17 @@ fn done_when_all_done() {
 fn done_when_all_done2() {
     test_done("{0, 0, 0, 0}");
 }
+
+#[test]
+fn expand() {
+    let a = expr_to_var("{Expand(Sequence(3))}").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..8 {
+        let mut actual: Vec<u32> = (0..4).map(|_| a.next()).collect();
+        actual.sort();
+
+        assert_eq!(actual, [0, 1, 2, 3]);
+        assert!(a.done());
+    }
+}
@@ -1,6 +1,8 @@
 mod util;
 use crate::util::*;
 
+use std::collections::HashSet;
+
 #[test]
 fn last() {
     let a = expr_to_var("Sequence(3)").unwrap();
@@ -135,3 +137,95 @@ fn zero_increment() {
         a.next();
     });
 }
+
+#[test]
+fn named_args() {
+    let a = expr_to_var("Sequence(increment = 4, last = 12)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<(u32, bool)> = vec![(0, false), (4, false), (8, false), (12, true)];
+    let actual: Vec<(u32, bool)> = (0..4).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn count() {
+    let a = expr_to_var("Sequence(10, count = 3, increment = 2)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<(u32, bool)> = vec![(10, false), (12, false), (14, true)]
+        .into_iter()
+        .cycle()
+        .take(9)
+        .collect();
+    let actual: Vec<(u32, bool)> = (0..9).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn wrap() {
+    let a = expr_to_var("Sequence(first = 6, count = 5, wrap = 8)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<(u32, bool)> = vec![(6, false), (7, false), (0, false), (1, false), (2, true)];
+    let actual: Vec<(u32, bool)> = (0..5).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn wrap_decrement() {
+    let a = expr_to_var("Sequence(first = 1, count = 4, increment = -1, wrap = 3)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<u32> = vec![1, 0, 2, 1];
+    let actual: Vec<u32> = (0..4).map(|_| a.next()).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn wrap_positional() {
+    let a = expr_to_var("Sequence(6, 5, 1, wrap = 8)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<u32> = vec![6, 7, 0, 1, 2];
+    let actual: Vec<u32> = (0..5).map(|_| a.next()).collect();
+
+    assert_eq!(expected, actual);
+    assert!(a.done());
+}
+
+#[test]
+fn ring_from_random_start() {
+    let a = expr_to_var("Sequence(first = [0, 15], count = 16, wrap = 16)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut starts = HashSet::new();
+    for _ in 0..50 {
+        let period: Vec<u32> = (0..16).map(|_| a.next()).collect();
+        assert!(a.done());
+
+        let visited: HashSet<u32> = period.iter().cloned().collect();
+        assert_eq!(visited.len(), 16);
+        starts.insert(period[0]);
+    }
+
+    assert!(starts.len() > 1);
+}
+
+#[test]
+#[should_panic(expected = "the count sub-expression `0x0` returned 0")]
+fn zero_count() {
+    expr_to_var("Sequence(count = 0)").unwrap();
+}
+
+#[test]
+fn bad_args() {
+    assert!(expr_to_var("Sequence(last = 3, count = 3)").is_err());
+    assert!(expr_to_var("Sequence(last = 3, wrap = 3)").is_err());
+    assert!(expr_to_var("Sequence(first = 3)").is_err());
+    assert!(expr_to_var("Sequence(1, 2, 3, 4, 5, 6)").is_err());
+}
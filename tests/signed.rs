@@ -0,0 +1,171 @@
+fn parse(s: &str) -> rvs::Model {
+    rvs::parse(&Default::default(), s).unwrap()
+}
+
+fn next_i32(model: &rvs::Model, name: &str) -> i32 {
+    model.get_variable_by_name(name).unwrap().borrow_mut().next_i32()
+}
+
+#[test]
+fn range() {
+    let model = parse("a: i32 = [-5, 5];");
+
+    for _ in 0..100 {
+        let value = next_i32(&model, "a");
+        assert!((-5..=5).contains(&value));
+    }
+}
+
+#[test]
+fn unsigned_range() {
+    let model = parse("a = [-5, 5];");
+
+    for _ in 0..100 {
+        let value = next_i32(&model, "a");
+        assert!(!(-4..=4).contains(&value));
+    }
+}
+
+#[test]
+fn operations() {
+    let model = parse(
+        "a: i32 = -8 >> 1; b: i32 = -7 / 2; c: i32 = -7 % 2; d: i32 = -1 < 1;
+         e = -8 >> 1; f = -1 < 1;",
+    );
+
+    assert_eq!(next_i32(&model, "a"), -4);
+    assert_eq!(next_i32(&model, "b"), -3);
+    assert_eq!(next_i32(&model, "c"), -1);
+    assert_eq!(next_i32(&model, "d"), 1);
+    assert_eq!(next_i32(&model, "e"), 0x7fff_fffc);
+    assert_eq!(next_i32(&model, "f"), 0);
+}
+
+#[test]
+fn width() {
+    let model = parse("a: i4 = Pattern(7, 8, -9); b: i4 saturate = Pattern(7, 8, -9);");
+    let a: Vec<i32> = (0..3).map(|_| next_i32(&model, "a")).collect();
+    let b: Vec<i32> = (0..3).map(|_| next_i32(&model, "b")).collect();
+
+    assert_eq!(a, [7, -8, 7]);
+    assert_eq!(b, [7, 7, -8]);
+}
+
+#[test]
+fn width_error() {
+    assert!(rvs::parse(&Default::default(), "a: i4 error = -9;").is_err());
+    assert!(rvs::parse(&Default::default(), "a: i4 error = [-8, 8];").is_err());
+    assert!(rvs::parse(&Default::default(), "a: i4 error = [-8, 7];").is_ok());
+}
+
+#[test]
+fn i64() {
+    let model = parse("a: i8 = -1; b = -1;");
+    let a = model.get_variable_by_name("a").unwrap();
+    let b = model.get_variable_by_name("b").unwrap();
+
+    assert_eq!(a.borrow_mut().next_i64(), -1);
+    assert_eq!(b.borrow_mut().next_i64(), 0xffff_ffff);
+    assert!(a.borrow().signed());
+    assert!(!b.borrow().signed());
+}
+
+#[test]
+fn enum_members() {
+    let model = parse("enum Delta { Back = -1, Stay, Forward } a: i32 = {Delta::Back, Delta::Forward};");
+
+    for _ in 0..100 {
+        let value = next_i32(&model, "a");
+        assert!(value == -1 || value == 1);
+    }
+}
+
+#[test]
+fn display() {
+    let model = parse("a: i8 = [-5, 0x5]; b: i8 = [-3, -3]; c: i8 = -3;");
+
+    assert_eq!(
+        model.to_string(),
+        "a: i8 = [-0x5, 0x5];\nb: i8 = -0x3;\nc: i8 = -0x3;\n"
+    );
+}
+
+#[test]
+fn display_types() {
+    let model = parse("a: i8 = [-5, 5] except {0, [-1, -3]}; b: i8 = Cyclic([2, -2]); c: i8 = Walk(0, 1, -4, 4);");
+
+    assert_eq!(
+        model.to_string(),
+        "a: i8 = [-0x5, 0x5] except {0x0, [-0x3, -0x1], };\nb: i8 = Cyclic([0x2, -0x2]);\n\
+         c: i8 = Walk(0x0, 0x1, -0x4, 0x4, wrap);\n"
+    );
+}
+
+#[test]
+fn except() {
+    let model = parse("a: i8 = [-5, 5] except {0, [-3, -1]}; b: i8 = {[-5, 5], 7} except {[-4, 4]};");
+
+    for _ in 0..100 {
+        let a = next_i32(&model, "a");
+        assert!([-5, -4, 1, 2, 3, 4, 5].contains(&a), "{}", a);
+
+        let b = next_i32(&model, "b");
+        assert!([-5, 5, 7].contains(&b), "{}", b);
+    }
+}
+
+#[test]
+fn cyclic() {
+    let model = parse("a: i8 = Cyclic([-2, 2]);");
+    let a = model.get_variable_by_name("a").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..3 {
+        let mut values: Vec<i32> = (0..5).map(|_| a.next_i32()).collect();
+        assert!(a.done());
+        values.sort();
+        assert_eq!(values, [-2, -1, 0, 1, 2]);
+    }
+}
+
+#[test]
+fn walk() {
+    let model = parse("a: i8 = Walk(0, [-1, 1], -4, 4, reflect); b: i8 = Walk(-4, -1, -4, -1);");
+
+    for _ in 0..1000 {
+        let a = next_i32(&model, "a");
+        assert!((-4..=4).contains(&a), "{}", a);
+    }
+
+    let b: Vec<i32> = (0..6).map(|_| next_i32(&model, "b")).collect();
+    assert_eq!(b, [-4, -1, -2, -3, -4, -1]);
+}
+
+#[test]
+fn near() {
+    let model = parse("a: i32 = Near(-2, 3); b: i32 = Near(0x7fff_fffe, 4);");
+
+    for _ in 0..100 {
+        let a = next_i32(&model, "a");
+        assert!((-5..=1).contains(&a), "{}", a);
+
+        let b = next_i32(&model, "b");
+        assert!(b >= 0x7fff_fffa, "{}", b);
+    }
+}
+
+#[test]
+fn sequence() {
+    let model = parse("a: i8 = Sequence(3, -3, -1); b: i8 = Sequence(-2, 2);");
+
+    let a: Vec<i32> = (0..7).map(|_| next_i32(&model, "a")).collect();
+    let b: Vec<i32> = (0..5).map(|_| next_i32(&model, "b")).collect();
+    assert_eq!(a, [3, 2, 1, 0, -1, -2, -3]);
+    assert_eq!(b, [-2, -1, 0, 1, 2]);
+}
+
+#[test]
+fn unsupported_types() {
+    assert!(rvs::parse(&Default::default(), "a: i8 = Ids(0, 4);").is_err());
+    assert!(rvs::parse(&Default::default(), "a: i32 = Alloc(0, 0x100, 4);").is_err());
+}
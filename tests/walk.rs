@@ -0,0 +1,94 @@
+mod util;
+use crate::util::*;
+
+#[test]
+fn wrap() {
+    let a = expr_to_var("Walk(2, 1, 0, 3, wrap)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<(u32, bool)> = vec![(2, true), (3, true), (0, true), (1, true), (2, true)];
+    let actual: Vec<(u32, bool)> = (0..5).map(|_| (a.next(), a.done())).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn reflect() {
+    let a = expr_to_var("Walk(start = 1, step = 0 - 1, lo = 0, hi = 3, boundary = reflect)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<u32> = vec![1, 0, 1, 0, 1];
+    let actual: Vec<u32> = (0..5).map(|_| a.next()).collect();
+
+    assert_eq!(expected, actual);
+
+    let a = expr_to_var("Walk(1, 3, 0, 3, reflect)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let expected: Vec<u32> = vec![1, 2, 1, 2, 1];
+    let actual: Vec<u32> = (0..5).map(|_| a.next()).collect();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn random_steps_stay_in_bounds() {
+    let a = expr_to_var("Walk(0x800, [0, 0x40] - 0x20, 0x100, 0xfff, reflect)").unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut prev = a.next();
+    for _ in 0..10000 {
+        let next = a.next();
+        assert!((0x100..=0xfff).contains(&next));
+
+        let distance = (i64::from(next) - i64::from(prev)).abs();
+        assert!(distance <= 0x20);
+        prev = next;
+    }
+}
+
+#[test]
+fn bad_boundary() {
+    assert!(expr_to_var("Walk(0, 1, 0, 3, bounce)").is_err());
+}
+
+#[test]
+fn near() {
+    let model = rvs::parse(
+        &Default::default(),
+        "a = [0x1000, 0x2000];
+         b = Near(a.prev, 0x10);",
+    ).unwrap();
+    let a = model.get_variable_by_name("a").unwrap();
+    let b = model.get_variable_by_name("b").unwrap();
+
+    for _ in 0..1000 {
+        let a = a.borrow_mut().next();
+        let b = b.borrow_mut().next();
+
+        assert!(b >= a - 0x10 && b <= a + 0x10);
+    }
+}
+
+#[test]
+fn near_clamps() {
+    let a = expr_to_var("Near(0, 4)").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..100 {
+        assert!(a.next() <= 4);
+        assert!(a.done());
+    }
+
+    let a = expr_to_var("Near(0xffffffff, 4)").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..100 {
+        assert!(a.next() >= 0xfffffffb);
+    }
+}
+
+#[test]
+fn host_driven_limits() {
+    assert!(rvs::parse(&Default::default(), "input d = 4; a = Walk(0, [0, 1], 0, d, reflect);").is_err());
+}
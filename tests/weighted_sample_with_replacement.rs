@@ -53,3 +53,25 @@ fn done_when_sub_expr_done() {
 
     assert_eq!(expected, actual);
 }
+
+#[test]
+fn large_weights() {
+    let a = expr_to_var("r{4_000_000_000: 0, 4_000_000_000: 1, 1: 2}").unwrap();
+    let mut a = a.borrow_mut();
+
+    let mut actual: HashMap<u32, u32> = HashMap::new();
+
+    for _ in 0..1000 {
+        let entry = actual.entry(a.next()).or_insert(0);
+        *entry += 1;
+    }
+
+    println!("expected:{{0: 500, 1: 500}} actual:{:?}", actual);
+    assert!(actual[&0] >= 500 - 50 && actual[&0] <= 500 + 50);
+    assert!(actual[&1] >= 500 - 50 && actual[&1] <= 500 + 50);
+}
+
+#[test]
+fn zero_weights() {
+    assert!(expr_to_var("r{0: 0, 0: 1}").is_err());
+}
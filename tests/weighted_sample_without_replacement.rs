@@ -20,3 +20,32 @@ fn distribution() {
         assert_eq!(expected, actual);
     }
 }
+
+#[test]
+fn done_at_end_of_pool() {
+    let a = expr_to_var("{2: 0, 0: 1, 1: 2}").unwrap();
+    let mut a = a.borrow_mut();
+
+    for _ in 0..100 {
+        let mut actual: HashMap<u32, u32> = HashMap::new();
+        for i in 0..3 {
+            let entry = actual.entry(a.next()).or_insert(0);
+            *entry += 1;
+            assert_eq!(a.done(), i == 2);
+        }
+
+        let expected: HashMap<u32, u32> = [(0, 2), (2, 1)].iter().cloned().collect();
+        assert_eq!(expected, actual);
+    }
+}
+
+#[test]
+fn large_weights() {
+    let a = expr_to_var("{1_000_000_000: 0, 1: 1}").unwrap();
+    let mut a = a.borrow_mut();
+
+    let actual: Vec<u32> = (0..1000).map(|_| a.next()).collect();
+
+    assert!(actual.iter().all(|value| *value == 0 || *value == 1));
+    assert!(!a.done());
+}
@@ -0,0 +1,89 @@
+fn parse(s: &str) -> rvs::Model {
+    rvs::parse(&Default::default(), s).unwrap()
+}
+
+#[test]
+fn truncate() {
+    let model = parse("a: u5 = Pattern(0x1f, 0x20, 0x25);");
+    let a = model.get_variable_by_name("a").unwrap();
+    let mut a = a.borrow_mut();
+
+    let values: Vec<u32> = (0..3).map(|_| a.next()).collect();
+    assert_eq!(values, [0x1f, 0x0, 0x5]);
+    assert_eq!(a.prev(), 0x5);
+    assert_eq!(a.width(), 5);
+}
+
+#[test]
+fn saturate() {
+    let model = parse("x = 0x10; y = 0x18; a: u5 saturate = x + y;");
+    let a = model.get_variable_by_name("a").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert_eq!(a.next(), 0x1f);
+    assert_eq!(a.prev(), 0x1f);
+}
+
+#[test]
+fn error() {
+    assert!(rvs::parse(&Default::default(), "a: u5 error = 0x20;").is_err());
+    assert!(rvs::parse(&Default::default(), "a: u5 error = [0, 0x20];").is_err());
+    assert!(rvs::parse(&Default::default(), "a: u5 error = {1, 0x20};").is_err());
+    assert!(rvs::parse(&Default::default(), "a: u5 error = [0, 0x1f];").is_ok());
+}
+
+#[test]
+fn overflowed() {
+    let model = parse("a: u5 error = Pattern(0x1f, 0x25);");
+    let a = model.get_variable_by_name("a").unwrap();
+    let mut a = a.borrow_mut();
+
+    assert_eq!(a.next(), 0x1f);
+    assert!(!a.overflowed());
+
+    assert_eq!(a.next(), 0x5);
+    assert!(a.overflowed());
+}
+
+#[test]
+fn references_see_limited_value() {
+    let model = parse("a: u4 = 0x1f; b = a + 1;");
+    let b = model.get_variable_by_name("b").unwrap();
+
+    assert_eq!(b.borrow_mut().next(), 0x10);
+}
+
+#[test]
+fn default_width() {
+    let model = parse("a = 1;");
+    let a = model.get_variable_by_name("a").unwrap();
+
+    assert_eq!(a.borrow().width(), 32);
+}
+
+#[test]
+fn sized_literal_width() {
+    let model = parse("a = 4'b1010; b: u8 = 4'b1010; c = 4'b1010 + 1;");
+    let width = |name| model.get_variable_by_name(name).unwrap().borrow().width();
+
+    assert_eq!(width("a"), 4);
+    assert_eq!(width("b"), 8);
+    assert_eq!(width("c"), 32);
+    assert_eq!(model.to_string(), "a: u4 = 0xa;\nb: u8 = 0xa;\nc = (0xa + 0x1);\n");
+}
+
+#[test]
+fn invalid_width() {
+    assert!(rvs::parse(&Default::default(), "a: u0 = 1;").is_err());
+    assert!(rvs::parse(&Default::default(), "a: u33 = 1;").is_err());
+}
+
+#[test]
+fn display() {
+    let model = parse("a: u5 = 1; b: u8 saturate = 2; c = 3;");
+
+    assert_eq!(
+        model.to_string(),
+        "a: u5 = 0x1;\nb: u8 saturate = 0x2;\nc = 0x3;\n"
+    );
+}